@@ -0,0 +1,120 @@
+//! Command-line arguments.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::{config::PortConfig, serial::port::device_details};
+
+/// Terminal UI for multi-port serial communication.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    /// Ports config file [default: ./config/ports.toml, then
+    /// $XDG_CONFIG_HOME/serial_tui/ports.toml]
+    #[arg(short, long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Directory for per-port logs and super.log
+    #[arg(long, value_name = "DIR", default_value = "logs")]
+    pub log_dir: PathBuf,
+
+    /// Disable file logging
+    #[arg(long, conflicts_with = "log_dir")]
+    pub no_log: bool,
+
+    /// Open an extra port, e.g. `dut=/dev/ttyUSB0:115200` (repeatable).
+    /// Name and baud rate are optional; the name defaults to the device
+    /// file name. Overrides a config section with the same name.
+    #[arg(short, long = "port", value_name = "[NAME=]PATH[:BAUD]", value_parser = parse_port)]
+    pub ports: Vec<(String, PortConfig)>,
+
+    /// List serial devices found on this system and exit
+    #[arg(long)]
+    pub list_ports: bool,
+
+    /// Run the `--run` files without the TUI, streaming port data to
+    /// stdout. Exits nonzero if any of them fails
+    #[arg(long, requires = "run")]
+    pub headless: bool,
+
+    /// Script, or macro file (`.toml`), to run in headless mode (repeatable)
+    #[arg(long, value_name = "FILE", requires = "headless")]
+    pub run: Vec<PathBuf>,
+
+    /// Write a JUnit XML report of the headless run
+    #[arg(long, value_name = "FILE", requires = "headless")]
+    pub junit: Option<PathBuf>,
+}
+
+/// Parses `[NAME=]PATH[:BAUD]` into a port name and config.
+///
+/// The `:BAUD` suffix is only taken when it is a number, so Windows
+/// paths like `\\.\COM10` and odd device names still work.
+fn parse_port(s: &str) -> Result<(String, PortConfig), String> {
+    let (name, spec) = match s.split_once('=') {
+        Some((name, spec)) => (Some(name.trim()), spec.trim()),
+        None => (None, s.trim()),
+    };
+
+    let (path, baud_rate) = match spec.rsplit_once(':') {
+        Some((path, baud)) if !baud.is_empty() && baud.bytes().all(|b| b.is_ascii_digit()) => {
+            let baud = baud
+                .parse()
+                .map_err(|e| format!("invalid baud rate: {e}"))?;
+            (path, Some(baud))
+        }
+        _ => (spec, None),
+    };
+
+    if path.is_empty() {
+        return Err("missing device path".into());
+    }
+
+    let name = match name {
+        Some("") => return Err("empty port name".into()),
+        Some(name) => name.to_string(),
+        None => path.rsplit(['/', '\\']).next().unwrap_or(path).to_string(),
+    };
+
+    let mut config = PortConfig {
+        path: path.into(),
+        ..Default::default()
+    };
+    if let Some(baud_rate) = baud_rate {
+        config.baud_rate = baud_rate;
+    }
+    Ok((name, config))
+}
+
+/// Prints the serial devices found on this system, one per line.
+pub fn list_ports() -> anyhow::Result<()> {
+    let ports = serialport::available_ports()?;
+    if ports.is_empty() {
+        eprintln!("No serial ports found");
+    }
+    for info in ports {
+        println!("{}  {}", info.port_name, device_details(&info.port_type));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_port() {
+        let (name, config) = parse_port("dut=/dev/ttyUSB0:9600").unwrap();
+        assert_eq!(name, "dut");
+        assert_eq!(config.path, PathBuf::from("/dev/ttyUSB0"));
+        assert_eq!(config.baud_rate, 9600);
+
+        let (name, config) = parse_port("/dev/ttyACM1").unwrap();
+        assert_eq!(name, "ttyACM1");
+        assert_eq!(config.baud_rate, PortConfig::default().baud_rate);
+
+        assert!(parse_port("dut=").is_err());
+        assert!(parse_port("=/dev/ttyUSB0").is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! Display pane layout and line format loaded from `layout.toml` next to
+//! `ports.toml`.
+//!
+//! Besides the combined pane showing every port, each group of ports gets
+//! its own pane. Without groups, every port gets a pane of its own.
+//!
+//! ```toml
+//! arrangement = "grid"    # horizontal (side by side), vertical (stacked) or grid
+//! split = true            # start split instead of zoomed on the combined pane
+//! line_format = "{time} {port}: {text}"
+//! timestamp = "relative"  # time, micros, date, relative, delta, delta_port or off
+//! port_tag = "auto"       # always, auto (not in single-port panes) or never
+//!
+//! [[panes]]
+//! name = "boards"
+//! ports = ["com1", "com2"]
+//!
+//! [[panes]]
+//! name = "modem"
+//! ports = ["modem"]
+//! ```
+
+use std::{fs, path::Path, str::FromStr};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::error::ConfigError;
+
+/// Layout file name, looked up in the same directory as `ports.toml`.
+pub const LAYOUT_FILE: &str = "layout.toml";
+
+/// How split panes share the display area.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Arrangement {
+    /// Side by side
+    Horizontal,
+    /// Stacked top to bottom
+    Vertical,
+    /// Rows and columns, as square as possible
+    #[default]
+    Grid,
+}
+
+impl Arrangement {
+    /// Returns the arrangement after this one (Horizontal -> Vertical -> Grid).
+    pub fn next(self) -> Self {
+        match self {
+            Arrangement::Horizontal => Arrangement::Vertical,
+            Arrangement::Vertical => Arrangement::Grid,
+            Arrangement::Grid => Arrangement::Horizontal,
+        }
+    }
+}
+
+/// How the `{time}` field of display lines is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampMode {
+    /// Time of day with milliseconds
+    #[default]
+    Time,
+    /// Time of day with microseconds
+    Micros,
+    /// Date and time of day
+    Date,
+    /// Seconds since the session started
+    Relative,
+    /// Seconds since the previous line
+    Delta,
+    /// Seconds since the previous line of the same port
+    DeltaPort,
+    /// No timestamp
+    Off,
+}
+
+impl TimestampMode {
+    /// Returns the mode after this one, in declaration order, wrapping.
+    pub fn next(self) -> Self {
+        match self {
+            TimestampMode::Time => TimestampMode::Micros,
+            TimestampMode::Micros => TimestampMode::Date,
+            TimestampMode::Date => TimestampMode::Relative,
+            TimestampMode::Relative => TimestampMode::Delta,
+            TimestampMode::Delta => TimestampMode::DeltaPort,
+            TimestampMode::DeltaPort => TimestampMode::Off,
+            TimestampMode::Off => TimestampMode::Time,
+        }
+    }
+
+    /// Short name shown in the display title.
+    pub fn label(self) -> &'static str {
+        match self {
+            TimestampMode::Time => "TIME",
+            TimestampMode::Micros => "USEC",
+            TimestampMode::Date => "DATE",
+            TimestampMode::Relative => "REL",
+            TimestampMode::Delta => "DELTA",
+            TimestampMode::DeltaPort => "DELTA/PORT",
+            TimestampMode::Off => "NO TIME",
+        }
+    }
+}
+
+/// Which panes show the `{port}` field of display lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortTag {
+    #[default]
+    Always,
+    /// Every pane except those showing a single port
+    Auto,
+    Never,
+}
+
+/// A field of the line format.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Time,
+    Port,
+    Text,
+}
+
+/// One space-separated item of the line format: an optional field with
+/// literal text around it, such as `[{port}]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatItem {
+    pub lead: String,
+    pub field: Option<Field>,
+    pub trail: String,
+}
+
+/// Template of a display line, e.g. `[{time}] [{port}] {text}`.
+///
+/// Items are separated by spaces and hold at most one field each. An item
+/// whose field is empty (timestamp off, port tag hidden) is left out along
+/// with its space. `{text}` must appear exactly once.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct LineFormat(Vec<FormatItem>);
+
+impl LineFormat {
+    pub fn items(&self) -> &[FormatItem] {
+        &self.0
+    }
+}
+
+impl Default for LineFormat {
+    fn default() -> Self {
+        "[{time}] [{port}] {text}".parse().unwrap()
+    }
+}
+
+impl FromStr for LineFormat {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ConfigError::InvalidLineFormat(format!("{s:?}: {reason}"));
+
+        let mut items = Vec::new();
+        for item in s.split(' ').filter(|item| !item.is_empty()) {
+            let Some((lead, rest)) = item.split_once('{') else {
+                items.push(FormatItem {
+                    lead: item.into(),
+                    field: None,
+                    trail: String::new(),
+                });
+                continue;
+            };
+            let (name, trail) = rest.split_once('}').ok_or_else(|| invalid("unclosed {"))?;
+            if trail.contains('{') {
+                return Err(invalid("more than one field in an item"));
+            }
+            let field = match name {
+                "time" => Field::Time,
+                "port" => Field::Port,
+                "text" => Field::Text,
+                other => return Err(invalid(&format!("unknown field {{{other}}}"))),
+            };
+            items.push(FormatItem {
+                lead: lead.into(),
+                field: Some(field),
+                trail: trail.into(),
+            });
+        }
+
+        let texts = items
+            .iter()
+            .filter(|item| item.field == Some(Field::Text))
+            .count();
+        if texts != 1 {
+            return Err(invalid("{text} must appear exactly once"));
+        }
+        Ok(Self(items))
+    }
+}
+
+impl TryFrom<String> for LineFormat {
+    type Error = ConfigError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// A pane showing the data of some ports.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PaneGroup {
+    /// Shown in the pane title
+    pub name: String,
+    pub ports: Vec<String>,
+}
+
+/// Contents of `layout.toml`.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Layout {
+    #[serde(default)]
+    pub arrangement: Arrangement,
+    /// Start with all panes shown instead of the combined pane zoomed
+    #[serde(default)]
+    pub split: bool,
+    /// Port groups; empty means one pane per port
+    #[serde(default)]
+    pub panes: Vec<PaneGroup>,
+    #[serde(default)]
+    pub line_format: LineFormat,
+    /// Initial timestamp mode of every pane
+    #[serde(default)]
+    pub timestamp: TimestampMode,
+    #[serde(default)]
+    pub port_tag: PortTag,
+}
+
+/// Loads the layout next to the ports config.
+///
+/// A missing file means the default layout; an unreadable or invalid one
+/// is an error.
+pub fn load_layout(config_path: &Path) -> Result<Layout> {
+    let path = config_path.with_file_name(LAYOUT_FILE);
+    if !path.exists() {
+        return Ok(Layout::default());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read layout: {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_layout() {
+        let layout: Layout = toml::from_str(
+            r#"
+            arrangement = "vertical"
+
+            [[panes]]
+            name = "boards"
+            ports = ["com1", "com2"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(layout.arrangement, Arrangement::Vertical);
+        assert!(!layout.split);
+        assert_eq!(
+            layout.panes,
+            [PaneGroup {
+                name: "boards".into(),
+                ports: vec!["com1".into(), "com2".into()],
+            }]
+        );
+
+        assert_eq!(toml::from_str::<Layout>("").unwrap(), Layout::default());
+        assert!(toml::from_str::<Layout>("arrangement = \"diagonal\"").is_err());
+    }
+
+    #[test]
+    fn test_line_format() {
+        let layout: Layout = toml::from_str(
+            r#"
+            line_format = "{time}  <{port}>: {text} |"
+            timestamp = "delta_port"
+            port_tag = "auto"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(layout.timestamp, TimestampMode::DeltaPort);
+        assert_eq!(layout.port_tag, PortTag::Auto);
+        let items = layout.line_format.items();
+        assert_eq!(items.len(), 4);
+        assert_eq!(
+            items[1],
+            FormatItem {
+                lead: "<".into(),
+                field: Some(Field::Port),
+                trail: ">:".into(),
+            }
+        );
+        assert_eq!(items[3].field, None);
+
+        for bad in [
+            "{time} {port}",
+            "{text} {text}",
+            "{date} {text}",
+            "{port}{text}",
+            "{text",
+        ] {
+            assert!(bad.parse::<LineFormat>().is_err(), "{bad}");
+        }
+    }
+}
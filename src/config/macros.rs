@@ -0,0 +1,247 @@
+//! Macro library loaded from `macros.toml` next to `ports.toml`.
+//!
+//! Each section is a named macro: a list of steps that send text or hex
+//! bytes and/or wait, optionally bound to a function key and limited to
+//! specific ports.
+//!
+//! ```toml
+//! [reboot]
+//! key = "F2"
+//! description = "Reset the modem and query it"
+//! ports = ["modem"]              # default: the current send group
+//! steps = [
+//!   { send = "AT+CFUN=1,1", delay_ms = 3000 },
+//!   { hex = "7F 00 A5" },        # raw bytes, no line ending
+//!   { send = "\\x03", line_ending = false },
+//!   { delay_ms = 500 },
+//!   { send = "ATI", ports = ["modem", "dut"] },
+//! ]
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+use bytes::Bytes;
+use serde::{Deserialize, Deserializer};
+
+use crate::{error::ConfigError, types::escape};
+
+/// Macro file name, looked up in the same directory as `ports.toml`.
+pub const MACROS_FILE: &str = "macros.toml";
+
+/// Function key (`F1`–`F12`) a macro is bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct FunctionKey(pub u8);
+
+impl FromStr for FunctionKey {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix(['F', 'f'])
+            .and_then(|n| n.parse().ok())
+            .filter(|n| (1..=12).contains(n))
+            .map(FunctionKey)
+            .ok_or_else(|| ConfigError::InvalidFunctionKey(s.into()))
+    }
+}
+
+impl TryFrom<String> for FunctionKey {
+    type Error = ConfigError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// One step of a macro: send data, then wait `delay`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "RawStep")]
+pub struct MacroStep {
+    /// Bytes to send, `None` for a pure delay
+    pub data: Option<Bytes>,
+    /// Append each port's `tx_line_ending` to `data`
+    pub line_ending: bool,
+    /// Target ports overriding the macro's
+    pub ports: Option<Vec<Arc<str>>>,
+    /// Wait after sending
+    pub delay: Duration,
+}
+
+/// A step as written in `macros.toml`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawStep {
+    /// Text with escapes, sent with the line ending by default
+    send: Option<String>,
+    /// Hex bytes, sent without line ending by default
+    hex: Option<String>,
+    line_ending: Option<bool>,
+    #[serde(default, deserialize_with = "port_names")]
+    ports: Option<Vec<Arc<str>>>,
+    delay_ms: Option<u64>,
+}
+
+impl TryFrom<RawStep> for MacroStep {
+    type Error = String;
+
+    fn try_from(raw: RawStep) -> Result<Self, Self::Error> {
+        let (data, default_ending) = match (raw.send, raw.hex) {
+            (Some(_), Some(_)) => return Err("a step takes `send` or `hex`, not both".into()),
+            (Some(text), None) => (Some(escape::unescape(&text)), true),
+            (None, Some(hex)) => (Some(escape::parse_hex(&hex)), false),
+            (None, None) if raw.delay_ms.is_some() => (None, false),
+            (None, None) => return Err("a step needs `send`, `hex` or `delay_ms`".into()),
+        };
+        let data = data
+            .transpose()
+            .map_err(|e| e.to_string())?
+            .map(Bytes::from);
+
+        Ok(MacroStep {
+            data,
+            line_ending: raw.line_ending.unwrap_or(default_ending),
+            ports: raw.ports,
+            delay: Duration::from_millis(raw.delay_ms.unwrap_or(0)),
+        })
+    }
+}
+
+/// Deserializes an optional list of port names.
+fn port_names<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<Arc<str>>>, D::Error> {
+    let names = Option::<Vec<String>>::deserialize(d)?;
+    Ok(names.map(|names| names.into_iter().map(Arc::from).collect()))
+}
+
+/// A named sequence of sends and delays.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Macro {
+    /// Shown next to the name in the macro popup
+    #[serde(default)]
+    pub description: String,
+    /// Function key that runs the macro
+    pub key: Option<FunctionKey>,
+    /// Target ports for all steps; `None` uses the send group at launch
+    #[serde(default, deserialize_with = "port_names")]
+    pub ports: Option<Vec<Arc<str>>>,
+    pub steps: Vec<MacroStep>,
+}
+
+/// Loads the macros next to the ports config, sorted by name.
+///
+/// A missing file means no macros; an unreadable or invalid one is an error.
+pub fn load_macros(config_path: &Path) -> Result<BTreeMap<String, Macro>> {
+    let path = config_path.with_file_name(MACROS_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    read_macros(&path)
+}
+
+/// Reads and parses a macro file, sorted by name.
+///
+/// Two macros bound to the same function key are an error.
+pub fn read_macros(path: &Path) -> Result<BTreeMap<String, Macro>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read macros: {}", path.display()))?;
+
+    let macros =
+        toml::from_str(&content).with_context(|| format!("failed to parse {}", path.display()))?;
+    check_keys(&macros).with_context(|| format!("failed to parse {}", path.display()))?;
+    Ok(macros)
+}
+
+/// Fails if two macros are bound to the same function key.
+fn check_keys(macros: &BTreeMap<String, Macro>) -> Result<()> {
+    let mut bound: HashMap<u8, &str> = HashMap::new();
+    for (name, mac) in macros {
+        if let Some(key) = mac.key
+            && let Some(other) = bound.insert(key.0, name)
+        {
+            bail!("F{} is bound to both {other} and {name}", key.0);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the ports named by the macros or their steps that are not in
+/// `known`, as (macro, port) pairs.
+pub fn unknown_ports<'a>(
+    macros: &'a BTreeMap<String, Macro>,
+    known: &[Arc<str>],
+) -> Vec<(&'a str, &'a str)> {
+    let mut seen = BTreeSet::new();
+    let mut unknown = Vec::new();
+    for (name, mac) in macros {
+        let step_ports = mac.steps.iter().filter_map(|step| step.ports.as_ref());
+        for port in mac.ports.iter().chain(step_ports).flatten() {
+            if !known.contains(port) && seen.insert((name.as_str(), port.as_ref())) {
+                unknown.push((name.as_str(), port.as_ref()));
+            }
+        }
+    }
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_macros() {
+        let macros: BTreeMap<String, Macro> = toml::from_str(
+            r#"
+            [boot]
+            key = "F3"
+            ports = ["dut"]
+            steps = [
+                { send = "\\x03", line_ending = false, delay_ms = 100 },
+                { hex = "7F 00" },
+                { delay_ms = 500 },
+                { send = "ATI", ports = ["modem"] },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        let boot = &macros["boot"];
+        assert_eq!(boot.key, Some(FunctionKey(3)));
+        assert_eq!(boot.steps.len(), 4);
+        assert_eq!(boot.steps[0].data.as_deref(), Some(&b"\x03"[..]));
+        assert!(!boot.steps[0].line_ending);
+        assert_eq!(boot.steps[0].delay, Duration::from_millis(100));
+        assert_eq!(boot.steps[1].data.as_deref(), Some(&[0x7f, 0x00][..]));
+        assert!(!boot.steps[1].line_ending);
+        assert_eq!(boot.steps[2].data, None);
+        assert!(boot.steps[3].line_ending);
+        assert_eq!(boot.steps[3].ports.as_deref(), Some(&["modem".into()][..]));
+
+        let bad = |toml| toml::from_str::<BTreeMap<String, Macro>>(toml).is_err();
+        assert!(bad(r#"m = { key = "F13", steps = [] }"#));
+        assert!(bad(r#"m = { steps = [{ send = "a", hex = "00" }] }"#));
+        assert!(bad(r#"m = { steps = [{ line_ending = false }] }"#));
+        assert!(bad(r#"m = { steps = [{ hex = "zz" }] }"#));
+
+        assert_eq!(unknown_ports(&macros, &["dut".into()]), [("boot", "modem")]);
+        assert!(unknown_ports(&macros, &["dut".into(), "modem".into()]).is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(MACROS_FILE);
+        let steps = "steps = [{ delay_ms = 1 }]";
+        fs::write(
+            &path,
+            format!("[a]\nkey = \"F2\"\n{steps}\n[b]\nkey = \"f2\"\n{steps}\n"),
+        )
+        .unwrap();
+        let err = format!("{:#}", read_macros(&path).unwrap_err());
+        assert!(err.contains("F2 is bound to both a and b"), "{err}");
+    }
+}
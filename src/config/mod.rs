@@ -1,12 +1,27 @@
 //! Configuration loading and management.
 
+pub mod layout;
+pub mod macros;
 pub mod port;
+pub mod triggers;
 
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result};
+use toml_edit::{DocumentMut, Item, Table};
 
 pub use port::PortConfig;
 
+/// Config path relative to the working directory.
 const CONFIG_PATH: &str = "config/ports.toml";
+/// Config path relative to the user config directory (e.g. `~/.config`).
+const USER_CONFIG_PATH: &str = "serial_tui/ports.toml";
+/// Input history path relative to the user state directory.
+const HISTORY_PATH: &str = "serial_tui/history";
 
 const DEFAULT_CONFIG: &str = r##"# SerialTUI Configuration
 #
@@ -18,8 +33,37 @@ const DEFAULT_CONFIG: &str = r##"# SerialTUI Configuration
 #
 # Optional:
 #   baud_rate  = 115200    # Baud rate
-#   line_ending = "lf"     # lf, cr, or crlf
+#   data_bits  = 8         # 5, 6, 7, or 8
+#   stop_bits  = 1         # 1 or 2
+#   parity     = "none"    # none, odd, or even
+#   flow_control = "none"  # none, software (XON/XOFF), or hardware (RTS/CTS)
+#   rx_delimiter = "lf"    # Splits received lines: lf, cr, crlf, nul, hex
+#                          # bytes ("0x7E"), escapes ('\x02') or a '/regex/'
+#                          # (a match at the end of the data so far waits
+#                          # until no more data is waiting)
+#   tx_line_ending = "lf"  # Appended to sent text: lf, cr, or crlf
+#   local_echo = true      # Show and log sent data as ">>" lines
 #   color      = "white"   # Named color or hex "#RRGGBB"
+#   reconnect  = true      # Reopen the port when it disappears and comes back
+#   reconnect_interval_ms = 500        # Initial delay between attempts
+#   reconnect_max_interval_ms = 5000   # Backoff doubles up to this delay
+#   reset_sequence = "dtr=off rts=on wait=100 rts=off"  # Run by /reset:
+#                          # dtr=/rts= on, off or toggle, break=ms, wait=ms
+#   flush_timeout_ms = 100             # Show a line without its ending (e.g. a
+#                                      # prompt) after this quiet period; 0 = never
+#
+# Triggers run actions when a received line matches a regex. They are checked
+# in the port threads, so they fire even while the UI is busy. Patterns see
+# each complete line once, without its CR/LF (^...$ matches a whole line):
+#   [[triggers]]
+#   ports = ["device1"]          # Optional, default all ports
+#   pattern = "Press any key to stop autoboot"
+#   send = " "                   # Written back to the matching port as-is
+#   notify = "message"           # Notification, bell and border flash
+#   marker = "text"              # Marker line in the display and logs
+#   pause = true                 # Stop following new output (G resumes)
+#   macro = "name"               # Run a macro from macros.toml
+#   cooldown_ms = 1000           # Minimum time between two firings
 
 # Example configuration:
 # [device1]
@@ -31,20 +75,177 @@ const DEFAULT_CONFIG: &str = r##"# SerialTUI Configuration
 # [device2]
 # path = "/dev/ttyUSB1"
 # baud_rate = 9600
+# data_bits = 7
+# parity = "even"
+# flow_control = "hardware"
 # color = "#FF5733"
 "##;
 
-/// Ensures the config file exists, creating it with defaults if missing.
-pub fn ensure_config() -> &'static str {
-    let path = Path::new(CONFIG_PATH);
+/// Resolves the config file to use, creating it with defaults if missing.
+///
+/// Order: `explicit` (from `--config`), `config/ports.toml` in the working
+/// directory, then `serial_tui/ports.toml` in the user config directory.
+/// When none exists, the default config is written to `explicit` if given,
+/// else to the user config directory (or locally if there is none).
+pub fn ensure_config(explicit: Option<PathBuf>) -> PathBuf {
+    let user_path = user_config_dir().map(|dir| dir.join(USER_CONFIG_PATH));
+
+    let path = match explicit {
+        Some(path) => path,
+        None if Path::new(CONFIG_PATH).exists() => CONFIG_PATH.into(),
+        None => user_path.unwrap_or_else(|| CONFIG_PATH.into()),
+    };
 
     if !path.exists() {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).ok();
         }
-        fs::write(path, DEFAULT_CONFIG).ok();
-        eprintln!("Created default config at {CONFIG_PATH}");
+        fs::write(&path, DEFAULT_CONFIG).ok();
+        eprintln!("Created default config at {}", path.display());
+    }
+
+    path
+}
+
+/// Returns `$XDG_CONFIG_HOME`, falling back to `~/.config` (or `%APPDATA%` on Windows).
+fn user_config_dir() -> Option<PathBuf> {
+    env_path("XDG_CONFIG_HOME")
+        .or_else(|| env_path("HOME").map(|home| home.join(".config")))
+        .or_else(|| env_path("APPDATA"))
+}
+
+/// Returns the input history file: `serial_tui/history` in `$XDG_STATE_HOME`,
+/// falling back to `~/.local/state` (or `%LOCALAPPDATA%` on Windows).
+pub fn history_path() -> Option<PathBuf> {
+    env_path("XDG_STATE_HOME")
+        .or_else(|| env_path("HOME").map(|home| home.join(".local/state")))
+        .or_else(|| env_path("LOCALAPPDATA"))
+        .map(|dir| dir.join(HISTORY_PATH))
+}
+
+/// Reads a non-empty path from an environment variable.
+fn env_path(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Watches the config file and calls `on_change` when it is modified.
+///
+/// Polls the modification time once per `WATCH_INTERVAL`; exits when
+/// `on_change` returns false (e.g. its receiver was dropped).
+pub fn spawn_watcher(path: PathBuf, mut on_change: impl FnMut() -> bool + Send + 'static) {
+    const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+    let modified = |path: &Path| -> Option<SystemTime> { fs::metadata(path).ok()?.modified().ok() };
+
+    std::thread::spawn(move || {
+        let mut last = modified(&path);
+        loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            let current = modified(&path);
+            if current != last {
+                last = current;
+                if !on_change() {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Writes a port section into the config file.
+///
+/// Replaces the keys of an existing `[name]` section in place (or appends a
+/// new one), so comments and all other sections are preserved. Keys left at
+/// their default value are omitted from new sections.
+pub fn save_port(path: impl AsRef<Path>, name: &str, config: &PortConfig) -> Result<()> {
+    let path = path.as_ref();
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(e).with_context(|| format!("failed to read config: {}", path.display()));
+        }
+    };
+    let mut doc: DocumentMut = content.parse().context("failed to parse config")?;
+
+    let values = to_table(config)?;
+    let defaults = to_table(&PortConfig::default())?;
+
+    let section = doc
+        .entry(name)
+        .or_insert_with(|| Item::Table(Table::new()))
+        .as_table_mut()
+        .with_context(|| format!("[{name}] is not a table"))?;
+
+    for (key, value) in values.iter() {
+        let is_default =
+            key != "path" && defaults.get(key).map(|d| d.to_string()) == Some(value.to_string());
+        if is_default && !section.contains_key(key) {
+            continue;
+        }
+        section[key] = value.clone();
+    }
+    // Superseded by rx_delimiter / tx_line_ending, which were just written
+    section.remove("line_ending");
+
+    fs::write(path, doc.to_string())
+        .with_context(|| format!("failed to write config: {}", path.display()))
+}
+
+/// Removes a port section from the config file, preserving everything else.
+pub fn remove_port(path: impl AsRef<Path>, name: &str) -> Result<()> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read config: {}", path.display()))?;
+    let mut doc: DocumentMut = content.parse().context("failed to parse config")?;
+
+    if doc.remove(name).is_some() {
+        fs::write(path, doc.to_string())
+            .with_context(|| format!("failed to write config: {}", path.display()))?;
     }
+    Ok(())
+}
+
+/// Serializes a port config into a standalone TOML table.
+fn to_table(config: &PortConfig) -> Result<Table> {
+    let doc: DocumentMut = toml::to_string(config)
+        .context("failed to serialize port config")?
+        .parse()
+        .context("failed to serialize port config")?;
+    Ok(doc.as_table().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    CONFIG_PATH
+    #[test]
+    fn test_save_port_preserves_other_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ports.toml");
+        fs::write(
+            &path,
+            "# top comment\n[com1]\npath = \"/dev/ttyUSB0\"\nbaud_rate = 9600\n",
+        )
+        .unwrap();
+
+        let config = PortConfig {
+            path: "/dev/ttyACM0".into(),
+            baud_rate: 57_600,
+            ..Default::default()
+        };
+        save_port(&path, "com2", &config).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# top comment"));
+        assert!(content.contains("[com2]"));
+        assert!(!content.contains("reconnect"));
+
+        let ports: std::collections::HashMap<String, PortConfig> =
+            toml::from_str(&content).unwrap();
+        assert_eq!(ports["com1"].baud_rate, 9600);
+        assert_eq!(ports["com2"], config);
+    }
 }
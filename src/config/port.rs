@@ -1,13 +1,20 @@
 //! Serial port configuration.
 
+use std::{fmt, ops::Range, path::PathBuf};
+
+use memchr::{memchr, memmem};
 use ratatui::style::Color as RatatuiColor;
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use regex::bytes::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{error::ConfigError, types::color::Color};
+use crate::{
+    error::ConfigError,
+    types::{color::Color, escape},
+};
 
-/// Line ending style for serial communication.
+/// Line ending appended to transmitted data.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(try_from = "String", into = "String")]
 #[allow(clippy::upper_case_acronyms)]
 pub enum LineEnding {
     /// Line Feed (`\n`)
@@ -29,8 +36,13 @@ impl LineEnding {
         }
     }
 
-    pub fn len(&self) -> usize {
-        self.as_bytes().len()
+    /// Returns the config file name of this line ending.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "lf",
+            LineEnding::Cr => "cr",
+            LineEnding::CrLf => "crlf",
+        }
     }
 }
 
@@ -47,18 +59,555 @@ impl std::str::FromStr for LineEnding {
     }
 }
 
+impl TryFrom<String> for LineEnding {
+    type Error = ConfigError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<LineEnding> for String {
+    fn from(le: LineEnding) -> Self {
+        le.as_str().into()
+    }
+}
+
+/// Separator that splits received data into lines.
+///
+/// Written in the config as a name (`lf`, `cr`, `crlf`, `nul`), hex bytes
+/// (`0x7E`), an escaped string (`\r\n`, `\x02`) or a `/regex/` over the
+/// raw bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RxDelimiter {
+    /// Fixed byte sequence
+    Bytes(Vec<u8>),
+    /// Regular expression; the matched bytes end the line. Only settled
+    /// data is matched, see `find`
+    Regex(Regex),
+}
+
+impl Default for RxDelimiter {
+    fn default() -> Self {
+        RxDelimiter::Bytes(b"\n".to_vec())
+    }
+}
+
+impl RxDelimiter {
+    /// Finds the first delimiter in `buf` and returns the bytes it covers.
+    ///
+    /// Unless `settled` (the port went quiet or was lost), more data may
+    /// follow, so a regex match ending at the end of `buf` is not accepted:
+    /// it could still grow, and `$` would match in the middle of a line.
+    pub fn find(&self, buf: &[u8], settled: bool) -> Option<Range<usize>> {
+        match self {
+            RxDelimiter::Bytes(delim) if delim.len() == 1 => {
+                memchr(delim[0], buf).map(|i| i..i + 1)
+            }
+            RxDelimiter::Bytes(delim) => memmem::find(buf, delim).map(|i| i..i + delim.len()),
+            RxDelimiter::Regex(re) => re
+                .find(buf)
+                .map(|m| m.range())
+                .filter(|range| settled || range.end < buf.len()),
+        }
+    }
+
+    /// Returns the length of the longest tail of `buf` that is the start
+    /// of a multi-byte delimiter, such as the `\r` of a `crlf` still
+    /// waiting for its `\n`.
+    pub fn partial_suffix(&self, buf: &[u8]) -> usize {
+        match self {
+            RxDelimiter::Bytes(delim) => (1..delim.len())
+                .rev()
+                .find(|&len| buf.ends_with(&delim[..len]))
+                .unwrap_or(0),
+            RxDelimiter::Regex(_) => 0,
+        }
+    }
+}
+
+impl std::str::FromStr for RxDelimiter {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ConfigError::InvalidRxDelimiter(format!("{s:?}: {reason}"));
+
+        if let Some(pattern) = s.strip_prefix('/').and_then(|p| p.strip_suffix('/')) {
+            let re = Regex::new(pattern).map_err(|e| invalid(&e.to_string()))?;
+            if re.is_match(b"") {
+                return Err(invalid("regex must not match empty input"));
+            }
+            return Ok(RxDelimiter::Regex(re));
+        }
+
+        let bytes = match s.to_lowercase().as_str() {
+            "lf" => b"\n".to_vec(),
+            "cr" => b"\r".to_vec(),
+            "crlf" => b"\r\n".to_vec(),
+            "nul" => vec![0],
+            lower if lower.starts_with("0x") => {
+                escape::parse_hex(s).map_err(|e| invalid(&e.to_string()))?
+            }
+            _ => escape::unescape(s).map_err(|e| invalid(&e.to_string()))?,
+        };
+        if bytes.is_empty() {
+            return Err(invalid("must not be empty"));
+        }
+        Ok(RxDelimiter::Bytes(bytes))
+    }
+}
+
+impl fmt::Display for RxDelimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = match self {
+            RxDelimiter::Regex(re) => return write!(f, "/{}/", re.as_str()),
+            RxDelimiter::Bytes(bytes) => bytes.as_slice(),
+        };
+        match bytes {
+            b"\n" => return f.write_str("lf"),
+            b"\r" => return f.write_str("cr"),
+            b"\r\n" => return f.write_str("crlf"),
+            [0] => return f.write_str("nul"),
+            _ => {}
+        }
+        for (i, &b) in bytes.iter().enumerate() {
+            match b {
+                b'\r' => f.write_str("\\r")?,
+                b'\n' => f.write_str("\\n")?,
+                b'\t' => f.write_str("\\t")?,
+                b'\\' => f.write_str("\\\\")?,
+                // A leading `/` would read back as a regex
+                b'/' if i == 0 => f.write_str("\\x2f")?,
+                b' '..=b'~' => write!(f, "{}", b as char)?,
+                _ => write!(f, "\\x{b:02x}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for RxDelimiter {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RxDelimiter::Bytes(a), RxDelimiter::Bytes(b)) => a == b,
+            (RxDelimiter::Regex(a), RxDelimiter::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl TryFrom<String> for RxDelimiter {
+    type Error = ConfigError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<RxDelimiter> for String {
+    fn from(delim: RxDelimiter) -> Self {
+        delim.to_string()
+    }
+}
+
+/// Number of data bits per character.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DataBits {
+    Five,
+    Six,
+    Seven,
+    #[default]
+    Eight,
+}
+
+impl DataBits {
+    /// Returns the number of bits as an integer.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            DataBits::Five => 5,
+            DataBits::Six => 6,
+            DataBits::Seven => 7,
+            DataBits::Eight => 8,
+        }
+    }
+}
+
+impl TryFrom<u8> for DataBits {
+    type Error = ConfigError;
+
+    fn try_from(bits: u8) -> Result<Self, Self::Error> {
+        match bits {
+            5 => Ok(DataBits::Five),
+            6 => Ok(DataBits::Six),
+            7 => Ok(DataBits::Seven),
+            8 => Ok(DataBits::Eight),
+            other => Err(ConfigError::InvalidDataBits(other.to_string())),
+        }
+    }
+}
+
+impl std::str::FromStr for DataBits {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse::<u8>()
+            .map_err(|_| ConfigError::InvalidDataBits(s.into()))
+            .and_then(DataBits::try_from)
+    }
+}
+
+impl From<DataBits> for serialport::DataBits {
+    fn from(bits: DataBits) -> Self {
+        match bits {
+            DataBits::Five => serialport::DataBits::Five,
+            DataBits::Six => serialport::DataBits::Six,
+            DataBits::Seven => serialport::DataBits::Seven,
+            DataBits::Eight => serialport::DataBits::Eight,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DataBits {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u8::deserialize(deserializer)?;
+        DataBits::try_from(bits).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for DataBits {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+/// Number of stop bits per character.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StopBits {
+    #[default]
+    One,
+    Two,
+}
+
+impl StopBits {
+    /// Returns the number of stop bits as an integer.
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for StopBits {
+    type Error = ConfigError;
+
+    fn try_from(bits: u8) -> Result<Self, Self::Error> {
+        match bits {
+            1 => Ok(StopBits::One),
+            2 => Ok(StopBits::Two),
+            other => Err(ConfigError::InvalidStopBits(other.to_string())),
+        }
+    }
+}
+
+impl std::str::FromStr for StopBits {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse::<u8>()
+            .map_err(|_| ConfigError::InvalidStopBits(s.into()))
+            .and_then(StopBits::try_from)
+    }
+}
+
+impl From<StopBits> for serialport::StopBits {
+    fn from(bits: StopBits) -> Self {
+        match bits {
+            StopBits::One => serialport::StopBits::One,
+            StopBits::Two => serialport::StopBits::Two,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StopBits {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u8::deserialize(deserializer)?;
+        StopBits::try_from(bits).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for StopBits {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+/// Parity checking mode.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(try_from = "String", into = "String")]
+pub enum Parity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+impl Parity {
+    /// Returns the config file name of this parity mode.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Parity::None => "none",
+            Parity::Odd => "odd",
+            Parity::Even => "even",
+        }
+    }
+
+    /// Returns the single-letter abbreviation used in framing notation (e.g. `8N1`).
+    pub fn as_char(&self) -> char {
+        match self {
+            Parity::None => 'N',
+            Parity::Odd => 'O',
+            Parity::Even => 'E',
+        }
+    }
+}
+
+impl std::str::FromStr for Parity {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" | "n" => Ok(Parity::None),
+            "odd" | "o" => Ok(Parity::Odd),
+            "even" | "e" => Ok(Parity::Even),
+            other => Err(ConfigError::InvalidParity(other.into())),
+        }
+    }
+}
+
+impl TryFrom<String> for Parity {
+    type Error = ConfigError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Parity> for String {
+    fn from(parity: Parity) -> Self {
+        parity.as_str().into()
+    }
+}
+
+impl From<Parity> for serialport::Parity {
+    fn from(parity: Parity) -> Self {
+        match parity {
+            Parity::None => serialport::Parity::None,
+            Parity::Odd => serialport::Parity::Odd,
+            Parity::Even => serialport::Parity::Even,
+        }
+    }
+}
+
+/// Flow control mode.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(try_from = "String", into = "String")]
+pub enum FlowControl {
+    #[default]
+    None,
+    /// XON/XOFF
+    Software,
+    /// RTS/CTS
+    Hardware,
+}
+
+impl FlowControl {
+    /// Returns the config file name of this flow control mode.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FlowControl::None => "none",
+            FlowControl::Software => "software",
+            FlowControl::Hardware => "hardware",
+        }
+    }
+}
+
+impl std::str::FromStr for FlowControl {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(FlowControl::None),
+            "software" | "xonxoff" => Ok(FlowControl::Software),
+            "hardware" | "rtscts" => Ok(FlowControl::Hardware),
+            other => Err(ConfigError::InvalidFlowControl(other.into())),
+        }
+    }
+}
+
+impl TryFrom<String> for FlowControl {
+    type Error = ConfigError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<FlowControl> for String {
+    fn from(flow: FlowControl) -> Self {
+        flow.as_str().into()
+    }
+}
+
+impl From<FlowControl> for serialport::FlowControl {
+    fn from(flow: FlowControl) -> Self {
+        match flow {
+            FlowControl::None => serialport::FlowControl::None,
+            FlowControl::Software => serialport::FlowControl::Software,
+            FlowControl::Hardware => serialport::FlowControl::Hardware,
+        }
+    }
+}
+
+/// One step of a control line sequence, run by the port's writer thread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlStep {
+    /// Set DTR, or toggle it with `None`
+    Dtr(Option<bool>),
+    /// Set RTS, or toggle it with `None`
+    Rts(Option<bool>),
+    /// Hold a BREAK condition for this many milliseconds
+    Break(u64),
+    /// Pause before the next step, in milliseconds
+    Wait(u64),
+}
+
+impl std::str::FromStr for ControlStep {
+    type Err = ConfigError;
+
+    /// Parses `dtr=on`, `rts=toggle`, `break=250` or `wait=100`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConfigError::InvalidControlStep(s.into());
+        let (key, value) = s.split_once('=').ok_or_else(invalid)?;
+        let level = || match value.to_lowercase().as_str() {
+            "on" | "1" | "high" => Ok(Some(true)),
+            "off" | "0" | "low" => Ok(Some(false)),
+            "toggle" => Ok(None),
+            _ => Err(invalid()),
+        };
+        let ms = || value.parse::<u64>().map_err(|_| invalid());
+
+        match key.to_lowercase().as_str() {
+            "dtr" => Ok(ControlStep::Dtr(level()?)),
+            "rts" => Ok(ControlStep::Rts(level()?)),
+            "break" => Ok(ControlStep::Break(ms()?)),
+            "wait" => Ok(ControlStep::Wait(ms()?)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for ControlStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = |level: Option<bool>| match level {
+            Some(true) => "on",
+            Some(false) => "off",
+            None => "toggle",
+        };
+        match self {
+            ControlStep::Dtr(on) => write!(f, "dtr={}", level(*on)),
+            ControlStep::Rts(on) => write!(f, "rts={}", level(*on)),
+            ControlStep::Break(ms) => write!(f, "break={ms}"),
+            ControlStep::Wait(ms) => write!(f, "wait={ms}"),
+        }
+    }
+}
+
+/// Control line steps run in order, written in the config as
+/// space-separated steps such as `"dtr=off rts=on wait=100 rts=off"`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ControlSequence(pub Vec<ControlStep>);
+
+impl std::str::FromStr for ControlSequence {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split([' ', ','])
+            .filter(|step| !step.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(ControlSequence)
+    }
+}
+
+impl fmt::Display for ControlSequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{step}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for ControlSequence {
+    type Error = ConfigError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<ControlSequence> for String {
+    fn from(sequence: ControlSequence) -> Self {
+        sequence.to_string()
+    }
+}
+
 /// Configuration for a single serial port connection.
+///
+/// Serde goes through the `Serialize`/`Deserialize` impls below, which
+/// accept the legacy `line_ending` key.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(default)]
+#[serde(remote = "Self", default)]
 pub struct PortConfig {
     /// Device path (e.g., "/dev/ttyUSB0", "COM3")
     pub path: PathBuf,
     /// Baud rate in bits per second
     pub baud_rate: u32,
-    /// Line ending style for transmitted data
-    pub line_ending: LineEnding,
+    /// Data bits per character
+    pub data_bits: DataBits,
+    /// Stop bits per character
+    pub stop_bits: StopBits,
+    /// Parity checking mode
+    pub parity: Parity,
+    /// Flow control mode
+    pub flow_control: FlowControl,
+    /// Separator that splits received data into lines
+    pub rx_delimiter: RxDelimiter,
+    /// Line ending appended to transmitted data
+    pub tx_line_ending: LineEnding,
+    /// Show and log sent data as TX lines
+    pub local_echo: bool,
     /// Display color for this port's output in the TUI
     pub color: Color,
+    /// Reopen the port automatically after it disappears
+    pub reconnect: bool,
+    /// Initial delay between reconnect attempts in milliseconds
+    pub reconnect_interval_ms: u64,
+    /// Upper bound for the exponential reconnect backoff in milliseconds
+    pub reconnect_max_interval_ms: u64,
+    /// Control line steps run by `/reset`, e.g. to reset a dev board
+    pub reset_sequence: ControlSequence,
+    /// Quiet period after which bytes without a line ending are shown
+    /// anyway (e.g. prompts); 0 waits for the line ending forever
+    pub flush_timeout_ms: u64,
 }
 
 impl Default for PortConfig {
@@ -66,12 +615,55 @@ impl Default for PortConfig {
         Self {
             path: PathBuf::new(),
             baud_rate: 115_200,
-            line_ending: LineEnding::default(),
+            data_bits: DataBits::default(),
+            stop_bits: StopBits::default(),
+            parity: Parity::default(),
+            flow_control: FlowControl::default(),
+            rx_delimiter: RxDelimiter::default(),
+            tx_line_ending: LineEnding::default(),
+            local_echo: true,
             color: Color(RatatuiColor::Reset),
+            reconnect: true,
+            reconnect_interval_ms: 500,
+            reconnect_max_interval_ms: 5_000,
+            reset_sequence: ControlSequence::default(),
+            flush_timeout_ms: 100,
         }
     }
 }
 
+impl Serialize for PortConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PortConfig::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PortConfig {
+    /// Deserializes a port section, mapping the legacy `line_ending` key
+    /// onto `rx_delimiter` and `tx_line_ending` where those are not set.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut table = toml::Table::deserialize(deserializer)?;
+        if let Some(line_ending) = table.remove("line_ending") {
+            for key in ["rx_delimiter", "tx_line_ending"] {
+                table.entry(key).or_insert_with(|| line_ending.clone());
+            }
+        }
+        PortConfig::deserialize(toml::Value::Table(table)).map_err(serde::de::Error::custom)
+    }
+}
+
+impl PortConfig {
+    /// Returns the framing in conventional notation, e.g. `8N1`.
+    pub fn framing(&self) -> String {
+        format!(
+            "{}{}{}",
+            self.data_bits.as_u8(),
+            self.parity.as_char(),
+            self.stop_bits.as_u8()
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,18 +676,147 @@ mod tests {
             PortConfig {
                 path: PathBuf::new(),
                 baud_rate: 115_200,
-                line_ending: LineEnding::Lf,
+                data_bits: DataBits::Eight,
+                stop_bits: StopBits::One,
+                parity: Parity::None,
+                flow_control: FlowControl::None,
+                rx_delimiter: RxDelimiter::Bytes(b"\n".to_vec()),
+                tx_line_ending: LineEnding::Lf,
+                local_echo: true,
                 color: Color(RatatuiColor::Reset),
+                reconnect: true,
+                reconnect_interval_ms: 500,
+                reconnect_max_interval_ms: 5_000,
+                reset_sequence: ControlSequence(Vec::new()),
+                flush_timeout_ms: 100,
             }
         );
     }
 
     #[test]
     fn test_modify() {
-        let mut port_config = PortConfig::default();
-        port_config.baud_rate = 9600;
-        port_config.line_ending = LineEnding::CrLf;
+        let port_config = PortConfig {
+            baud_rate: 9600,
+            tx_line_ending: LineEnding::CrLf,
+            ..Default::default()
+        };
         assert_eq!(port_config.baud_rate, 9600);
-        assert_eq!(port_config.line_ending, LineEnding::CrLf);
+        assert_eq!(port_config.tx_line_ending, LineEnding::CrLf);
+    }
+
+    #[test]
+    fn test_parse_framing() {
+        let port_config: PortConfig = toml::from_str(
+            r#"
+            path = "/dev/ttyUSB0"
+            data_bits = 7
+            stop_bits = 2
+            parity = "even"
+            flow_control = "hardware"
+            tx_line_ending = "crlf"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(port_config.data_bits, DataBits::Seven);
+        assert_eq!(port_config.stop_bits, StopBits::Two);
+        assert_eq!(port_config.parity, Parity::Even);
+        assert_eq!(port_config.flow_control, FlowControl::Hardware);
+        assert_eq!(port_config.tx_line_ending, LineEnding::CrLf);
+        assert_eq!(port_config.framing(), "7E2");
+    }
+
+    #[test]
+    fn test_invalid_framing() {
+        assert!(toml::from_str::<PortConfig>("data_bits = 9").is_err());
+        assert!(toml::from_str::<PortConfig>("stop_bits = 3").is_err());
+        assert!(toml::from_str::<PortConfig>(r#"parity = "mark""#).is_err());
+        assert!(toml::from_str::<PortConfig>(r#"flow_control = "dtr""#).is_err());
+    }
+
+    #[test]
+    fn test_legacy_line_ending() {
+        let port_config: PortConfig = toml::from_str(r#"line_ending = "crlf""#).unwrap();
+        assert_eq!(port_config.rx_delimiter, "crlf".parse().unwrap());
+        assert_eq!(port_config.tx_line_ending, LineEnding::CrLf);
+
+        // The new keys win over the legacy one
+        let port_config: PortConfig = toml::from_str(
+            r#"
+            line_ending = "cr"
+            rx_delimiter = "crlf"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(port_config.rx_delimiter, "crlf".parse().unwrap());
+        assert_eq!(port_config.tx_line_ending, LineEnding::Cr);
+    }
+
+    #[test]
+    fn test_rx_delimiter() {
+        let nul: RxDelimiter = "nul".parse().unwrap();
+        assert_eq!(nul.find(b"ab\0cd", false), Some(2..3));
+
+        let crlf: RxDelimiter = "crlf".parse().unwrap();
+        assert_eq!(crlf.partial_suffix(b"login: \r"), 1);
+        assert_eq!(crlf.partial_suffix(b"login: "), 0);
+        assert_eq!(nul.partial_suffix(b"ab"), 0);
+
+        let hdlc: RxDelimiter = "0x7E".parse().unwrap();
+        assert_eq!(hdlc, RxDelimiter::Bytes(vec![0x7e]));
+        assert_eq!("\\x7e".parse::<RxDelimiter>().unwrap(), hdlc);
+
+        let re: RxDelimiter = "/\\r?\\n/".parse().unwrap();
+        assert_eq!(re.find(b"ok\r\nnext", false), Some(2..4));
+        assert_eq!(re.find(b"ok\nnext", false), Some(2..3));
+        // A match at the end of unsettled data may still grow
+        assert_eq!(re.find(b"ok\r", false), None);
+
+        let prompt: RxDelimiter = "/>\\s*$/".parse().unwrap();
+        assert_eq!(prompt.find(b"a > b\nlogin> ", false), None);
+        assert_eq!(prompt.find(b"a > b\nlogin> ", true), Some(11..13));
+
+        for delim in [
+            "lf",
+            "crlf",
+            "nul",
+            "~",
+            "\\x02\\x03",
+            "\\x2fx/",
+            "/>\\s*$/",
+        ] {
+            let parsed: RxDelimiter = delim.parse().unwrap();
+            assert_eq!(parsed.to_string(), delim);
+        }
+
+        assert!("".parse::<RxDelimiter>().is_err());
+        assert!("0x7".parse::<RxDelimiter>().is_err());
+        assert!("/a*/".parse::<RxDelimiter>().is_err());
+        assert!("\\q".parse::<RxDelimiter>().is_err());
+    }
+
+    #[test]
+    fn test_reset_sequence() {
+        let port_config: PortConfig =
+            toml::from_str(r#"reset_sequence = "dtr=off, rts=ON wait=100 rts=toggle break=250""#)
+                .unwrap();
+        assert_eq!(
+            port_config.reset_sequence.0,
+            [
+                ControlStep::Dtr(Some(false)),
+                ControlStep::Rts(Some(true)),
+                ControlStep::Wait(100),
+                ControlStep::Rts(None),
+                ControlStep::Break(250),
+            ]
+        );
+        assert_eq!(
+            port_config.reset_sequence.to_string(),
+            "dtr=off rts=on wait=100 rts=toggle break=250"
+        );
+
+        assert!("dtr".parse::<ControlSequence>().is_err());
+        assert!("dtr=maybe".parse::<ControlSequence>().is_err());
+        assert!("wait=-1".parse::<ControlSequence>().is_err());
+        assert!("cts=on".parse::<ControlSequence>().is_err());
     }
 }
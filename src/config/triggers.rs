@@ -0,0 +1,175 @@
+//! Pattern-triggered actions from the `[[triggers]]` section of `ports.toml`.
+//!
+//! Each port's reader thread checks every complete received line, without
+//! its CR/LF, against the triggers, so auto-responders and alerts fire even
+//! while the UI is busy. Partial fragments wait for the rest of their line.
+//!
+//! ```toml
+//! [[triggers]]
+//! ports = ["com1"]                # default: all ports
+//! pattern = "Press any key to stop autoboot"
+//! send = " "                      # written back to the matching port as-is
+//!
+//! [[triggers]]
+//! pattern = "panic|HardFault"
+//! notify = "crashed"              # notification, bell and border flash
+//! marker = "CRASH"                # marker line in the display and logs
+//! pause = true                    # stop following new output
+//! macro = "dump_regs"             # run a macro from macros.toml
+//! cooldown_ms = 5000              # per port, default 1000
+//! ```
+
+use std::{sync::Arc, time::Duration};
+
+use bytes::Bytes;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::types::escape;
+
+/// Section holding the triggers in `ports.toml`; not a port name.
+pub const TRIGGERS_KEY: &str = "triggers";
+
+/// Something a trigger does when its pattern matches.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerAction {
+    /// Write bytes to the port that matched
+    Send(Bytes),
+    /// Show a notification, ring the bell and flash the display border
+    Notify(String),
+    /// Add a marker line to the display and the logs
+    Marker(String),
+    /// Pause following new output in the display
+    Pause,
+    /// Run a macro by name
+    RunMacro(String),
+}
+
+/// A pattern and the actions run when a received line matches it.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "RawTrigger")]
+pub struct Trigger {
+    /// Ports whose data is checked; `None` checks all
+    pub ports: Option<Vec<Arc<str>>>,
+    pub pattern: Regex,
+    pub actions: Vec<TriggerAction>,
+    /// Minimum time between two firings on the same port
+    pub cooldown: Duration,
+}
+
+/// A trigger as written in `ports.toml`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTrigger {
+    ports: Option<Vec<String>>,
+    pattern: String,
+    /// Text with escapes, sent without a line ending
+    send: Option<String>,
+    notify: Option<String>,
+    marker: Option<String>,
+    #[serde(default)]
+    pause: bool,
+    #[serde(rename = "macro")]
+    run_macro: Option<String>,
+    cooldown_ms: Option<u64>,
+}
+
+impl TryFrom<RawTrigger> for Trigger {
+    type Error = String;
+
+    fn try_from(raw: RawTrigger) -> Result<Self, Self::Error> {
+        let pattern = Regex::new(&raw.pattern).map_err(|e| e.to_string())?;
+
+        let mut actions = Vec::new();
+        if let Some(send) = raw.send {
+            let data = escape::unescape(&send).map_err(|e| e.to_string())?;
+            actions.push(TriggerAction::Send(Bytes::from(data)));
+        }
+        actions.extend(raw.notify.map(TriggerAction::Notify));
+        actions.extend(raw.marker.map(TriggerAction::Marker));
+        if raw.pause {
+            actions.push(TriggerAction::Pause);
+        }
+        actions.extend(raw.run_macro.map(TriggerAction::RunMacro));
+        if actions.is_empty() {
+            return Err(format!(
+                "trigger /{pattern}/ needs send, notify, marker, pause or macro"
+            ));
+        }
+
+        Ok(Trigger {
+            ports: raw
+                .ports
+                .map(|ports| ports.into_iter().map(Arc::from).collect()),
+            pattern,
+            actions,
+            cooldown: Duration::from_millis(raw.cooldown_ms.unwrap_or(1000)),
+        })
+    }
+}
+
+impl Trigger {
+    /// Returns true if the trigger watches `port` and `text` matches it.
+    ///
+    /// Cooldowns are left to the caller.
+    pub fn matches(&self, port: &str, text: &str) -> bool {
+        self.ports
+            .as_ref()
+            .is_none_or(|ports| ports.iter().any(|p| p.as_ref() == port))
+            && self.pattern.is_match(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Config {
+        triggers: Vec<Trigger>,
+    }
+
+    #[test]
+    fn test_triggers() {
+        let config: Config = toml::from_str(
+            r#"
+            [[triggers]]
+            ports = ["com1"]
+            pattern = "autoboot"
+            send = " \\r"
+            cooldown_ms = 60000
+
+            [[triggers]]
+            pattern = "panic|HardFault"
+            notify = "crashed"
+            pause = true
+            macro = "dump"
+            "#,
+        )
+        .unwrap();
+
+        let [autoboot, crash] = &config.triggers[..] else {
+            panic!("expected two triggers");
+        };
+        assert_eq!(autoboot.actions, [TriggerAction::Send(Bytes::from(" \r"))]);
+        assert_eq!(
+            crash.actions,
+            [
+                TriggerAction::Notify("crashed".into()),
+                TriggerAction::Pause,
+                TriggerAction::RunMacro("dump".into()),
+            ]
+        );
+        assert_eq!(crash.cooldown, Duration::from_secs(1));
+
+        assert!(!autoboot.matches("com2", "Hit any key to stop autoboot"));
+        assert!(autoboot.matches("com1", "Hit any key to stop autoboot"));
+        assert!(!autoboot.matches("com1", "Booting"));
+        assert!(crash.matches("com2", "Kernel panic"));
+
+        let bad = |toml| toml::from_str::<Config>(toml).is_err();
+        assert!(bad("[[triggers]]\npattern = \"x\""));
+        assert!(bad("[[triggers]]\npattern = \"(\"\npause = true"));
+        assert!(bad("[[triggers]]\npattern = \"x\"\nsend = \"\\\\q\""));
+    }
+}
@@ -11,6 +11,47 @@ pub enum ConfigError {
     #[error("invalid line ending: {0}")]
     InvalidLineEnding(String),
 
+    #[error("invalid rx delimiter: {0}")]
+    InvalidRxDelimiter(String),
+
+    #[error("invalid data bits: {0} (expected 5, 6, 7 or 8)")]
+    InvalidDataBits(String),
+
+    #[error("invalid stop bits: {0} (expected 1 or 2)")]
+    InvalidStopBits(String),
+
+    #[error("invalid parity: {0} (expected none, odd or even)")]
+    InvalidParity(String),
+
+    #[error("invalid flow control: {0} (expected none, software or hardware)")]
+    InvalidFlowControl(String),
+
+    #[error("invalid function key: {0} (expected F1 to F12)")]
+    InvalidFunctionKey(String),
+
+    #[error("invalid control step: {0} (expected dtr=, rts= on/off/toggle, break=ms or wait=ms)")]
+    InvalidControlStep(String),
+
+    #[error("invalid line format: {0}")]
+    InvalidLineFormat(String),
+
     #[error("invalid hex value: {0}")]
     ParseInt(#[from] std::num::ParseIntError),
 }
+
+/// Errors from parsing bytes typed as escapes or hex.
+#[derive(Debug, Error)]
+pub enum BytesError {
+    #[error("invalid escape sequence: {0} (expected \\r, \\n, \\t, \\0, \\\\ or \\xHH)")]
+    InvalidEscape(String),
+
+    #[error("invalid hex byte: {0}")]
+    InvalidHex(String),
+}
+
+/// Errors from loading an expect/send script.
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+}
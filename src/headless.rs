@@ -0,0 +1,368 @@
+//! Headless mode for CI: runs scripts and macro files without the TUI.
+//!
+//! Port data is streamed to stdout in the super.log format, status goes to
+//! stderr. Each script, and each macro of a macro file, is one test case;
+//! the results can be written as a JUnit XML report.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use chrono::Local;
+
+use crate::{
+    config::{
+        macros::{read_macros, unknown_ports},
+        triggers::TriggerAction,
+    },
+    logger::SuperLogFormat,
+    macro_runner,
+    script::{
+        Script,
+        runner::{self, Outcome},
+    },
+    serial::{Direction, PortEvent, hub::SerialHub},
+    ui::UiEvent,
+};
+
+/// How often a wait re-checks that the runner thread is alive.
+const POLL: Duration = Duration::from_millis(100);
+
+/// Result of one script or macro.
+pub struct TestCase {
+    pub name: String,
+    pub time: Duration,
+    /// Why the case failed, `None` if it passed
+    pub failure: Option<String>,
+}
+
+/// Drives the runs, doing the hub work the UI would do.
+struct Session {
+    hub: SerialHub,
+    ui_tx: mpsc::Sender<UiEvent>,
+    ui_rx: mpsc::Receiver<UiEvent>,
+    stdout_format: SuperLogFormat,
+}
+
+/// Runs each file in order and reports the results.
+///
+/// Files ending in `.toml` are macro files; anything else is a script.
+/// All ports are closed and their last data printed before returning, so
+/// the logger can finish.
+/// Returns true if every case passed.
+pub fn run(
+    hub: SerialHub,
+    ui_tx: mpsc::Sender<UiEvent>,
+    ui_rx: mpsc::Receiver<UiEvent>,
+    files: &[PathBuf],
+    junit: Option<&Path>,
+) -> Result<bool> {
+    let mut session = Session {
+        hub,
+        ui_tx,
+        ui_rx,
+        stdout_format: SuperLogFormat::default(),
+    };
+
+    let mut cases = Vec::new();
+    for path in files {
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            session.run_macros(path, &mut cases);
+        } else {
+            cases.push(session.run_script(path));
+        }
+    }
+    session.close();
+    session.drain();
+
+    let failed = cases.iter().filter(|case| case.failure.is_some()).count();
+    eprintln!("{} passed, {failed} failed", cases.len() - failed);
+
+    if let Some(path) = junit {
+        let file =
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        write_junit(&mut out, &cases)
+            .and_then(|()| out.flush())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(failed == 0)
+}
+
+impl Session {
+    fn run_script(&mut self, path: &Path) -> TestCase {
+        let name = path.display().to_string();
+        let start = Instant::now();
+        let failure = match Script::load(path) {
+            Ok(script) => {
+                let (_, thread) = runner::spawn(script, self.hub.subscribe(), self.ui_tx.clone());
+                self.wait_finished(&name, &thread)
+            }
+            Err(e) => Some(format!("{e:#}")),
+        };
+        report(name, start, failure)
+    }
+
+    /// Plays every macro in the file, in name order, each as its own case.
+    ///
+    /// Macros without `ports` go to all ports. A macro naming a port that
+    /// is not open fails without running.
+    fn run_macros(&mut self, path: &Path, cases: &mut Vec<TestCase>) {
+        let macros = match read_macros(path) {
+            Ok(macros) => macros,
+            Err(e) => {
+                let name = path.display().to_string();
+                cases.push(report(name, Instant::now(), Some(format!("{e:#}"))));
+                return;
+            }
+        };
+
+        let all_ports: Vec<Arc<str>> = self
+            .hub
+            .list_ports()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        let unknown = unknown_ports(&macros, &all_ports);
+        for (name, mac) in &macros {
+            let case_name = format!("{}::{name}", path.display());
+            let start = Instant::now();
+            if let Some((_, port)) = unknown.iter().find(|(mac, _)| mac == name) {
+                cases.push(report(
+                    case_name,
+                    start,
+                    Some(format!("unknown port {port}")),
+                ));
+                continue;
+            }
+            let ports = mac.ports.clone().unwrap_or_else(|| all_ports.clone());
+            let (_, thread) =
+                macro_runner::spawn(name.as_str().into(), mac.clone(), ports, self.ui_tx.clone());
+            let failure = self.wait_finished(&case_name, &thread);
+            cases.push(report(case_name, start, failure));
+        }
+    }
+
+    /// Handles events until the running script or macro finishes.
+    ///
+    /// Returns the first failure: the script's, a send error, or the
+    /// runner `thread` dying without finishing.
+    fn wait_finished(&mut self, name: &str, thread: &JoinHandle<()>) -> Option<String> {
+        let mut failure = None;
+        loop {
+            let event = if thread.is_finished() {
+                // Everything it sent is queued by now, so an empty queue
+                // means it panicked before reporting its result
+                match self.ui_rx.try_recv() {
+                    Ok(event) => event,
+                    Err(_) => return Some("runner thread panicked".into()),
+                }
+            } else {
+                match self.ui_rx.recv_timeout(POLL) {
+                    Ok(event) => event,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        return Some("event channel closed".into());
+                    }
+                }
+            };
+            if self.handle(event, name, &mut failure) {
+                return failure;
+            }
+        }
+    }
+
+    /// Prints the events still queued, such as data received after the
+    /// last run.
+    fn drain(&mut self) {
+        while let Ok(event) = self.ui_rx.try_recv() {
+            self.handle(event, "", &mut None);
+        }
+    }
+
+    /// Handles one event of the run `name`, recording a failure in
+    /// `failure`. Returns true once the run has finished.
+    fn handle(&mut self, event: UiEvent, name: &str, failure: &mut Option<String>) -> bool {
+        match event {
+            UiEvent::PortData(event) => self.print(&event),
+            UiEvent::ShowNotification(message) => eprintln!("{message}"),
+            UiEvent::ConnectionChanged(port, state) => eprintln!("{port}: {state}"),
+            UiEvent::ModemChanged(port, lines) => eprintln!("{port}: {lines}"),
+            UiEvent::ConfigChanged | UiEvent::ScriptProgress(_) => {}
+            UiEvent::Send {
+                ports,
+                data,
+                line_ending,
+            } => {
+                let result = if line_ending {
+                    self.hub.send(&ports, data)
+                } else {
+                    self.hub.send_raw(&ports, data)
+                };
+                if let Err(e) = result {
+                    eprintln!("{name}: send failed: {e}");
+                    failure.get_or_insert(format!("send failed: {e}"));
+                }
+            }
+            UiEvent::ScriptLog(message) => self.print(&PortEvent {
+                port: name.into(),
+                data: Bytes::from(format!("{message}\n")),
+                timestamp: Local::now(),
+                partial: false,
+                direction: Direction::Rx,
+            }),
+            UiEvent::TriggerFired(port, TriggerAction::Notify(message)) => {
+                eprintln!("{port}: {message}");
+            }
+            UiEvent::TriggerFired(port, TriggerAction::Marker(text)) => {
+                self.print_marker(port, &text);
+            }
+            UiEvent::ControlLines(port, sequence) => {
+                self.print_marker(port, &sequence.to_string());
+            }
+            // Display-only, and no macros alongside the running file
+            UiEvent::TriggerFired(..) => {}
+            UiEvent::MacroFinished(_) => return true,
+            UiEvent::ScriptFinished(outcome) => {
+                match outcome {
+                    Outcome::Passed(_) => {}
+                    Outcome::Failed(message) => *failure = Some(message),
+                    Outcome::Stopped => *failure = Some("stopped".into()),
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Streams an event to stdout like super.log.
+    fn print(&mut self, event: &PortEvent) {
+        let mut stdout = io::stdout().lock();
+        let _ = self.stdout_format.write(&mut stdout, event);
+        let _ = stdout.flush();
+    }
+
+    /// Prints a marker line like the logger writes it, on a line of its own.
+    fn print_marker(&mut self, port: Arc<str>, text: &str) {
+        let _ = self.stdout_format.end_line(&mut io::stdout());
+        self.print(&PortEvent {
+            port,
+            data: Bytes::from(format!("--- {text} ---\n")),
+            timestamp: Local::now(),
+            partial: false,
+            direction: Direction::Rx,
+        });
+    }
+
+    /// Closes every port so their reader threads drop the logger sender.
+    fn close(&mut self) {
+        for (name, _) in self.hub.list_ports() {
+            let _ = self.hub.close(&name);
+        }
+    }
+}
+
+/// Prints a case's result to stderr and builds its `TestCase`.
+fn report(name: String, start: Instant, failure: Option<String>) -> TestCase {
+    let time = start.elapsed();
+    match &failure {
+        None => eprintln!("PASS {name} ({:.2}s)", time.as_secs_f64()),
+        Some(message) => eprintln!("FAIL {name} ({:.2}s): {message}", time.as_secs_f64()),
+    }
+    TestCase {
+        name,
+        time,
+        failure,
+    }
+}
+
+/// Writes the cases as a JUnit XML report with a single test suite.
+pub fn write_junit(out: &mut impl Write, cases: &[TestCase]) -> io::Result<()> {
+    let failures = cases.iter().filter(|case| case.failure.is_some()).count();
+    let time: f64 = cases.iter().map(|case| case.time.as_secs_f64()).sum();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuite name="serial_tui" tests="{}" failures="{failures}" time="{time:.3}" timestamp="{}">"#,
+        cases.len(),
+        Local::now().format("%Y-%m-%dT%H:%M:%S"),
+    )?;
+    for case in cases {
+        write!(
+            out,
+            r#"  <testcase name="{}" classname="serial_tui" time="{:.3}""#,
+            xml_escape(&case.name),
+            case.time.as_secs_f64(),
+        )?;
+        match &case.failure {
+            None => writeln!(out, "/>")?,
+            Some(message) => {
+                writeln!(out, ">")?;
+                writeln!(out, r#"    <failure message="{}"/>"#, xml_escape(message))?;
+                writeln!(out, "  </testcase>")?;
+            }
+        }
+    }
+    writeln!(out, "</testsuite>")
+}
+
+/// Escapes text for an XML attribute value.
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' => out.push_str("&#10;"),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_junit() {
+        let cases = [
+            TestCase {
+                name: "boot.rhai".into(),
+                time: Duration::from_millis(1500),
+                failure: None,
+            },
+            TestCase {
+                name: "smoke.rhai".into(),
+                time: Duration::from_millis(250),
+                failure: Some("line 3: timed out waiting for /<ok>/ & \"done\"".into()),
+            },
+        ];
+        let mut out = Vec::new();
+        write_junit(&mut out, &cases).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+
+        assert!(xml.contains(r#"tests="2" failures="1" time="1.750""#));
+        assert!(
+            xml.contains(r#"<testcase name="boot.rhai" classname="serial_tui" time="1.500"/>"#)
+        );
+        assert!(xml.contains(
+            r#"<failure message="line 3: timed out waiting for /&lt;ok&gt;/ &amp; &quot;done&quot;"/>"#
+        ));
+    }
+}
@@ -1,17 +1,31 @@
-//! Serial data logger — writes per-port and combined super log files.
+//! Serial data logger — writes per-port and combined super log files
+//! into the log directory (`logs/` unless overridden with `--log-dir`).
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::{self, File, OpenOptions},
-    io::{Seek, Write},
+    io::{self, Seek, Write},
+    path::{Path, PathBuf},
     sync::{Arc, mpsc},
 };
 
-use crate::{serial::PortEvent, ui::UiEvent};
+use bytes::Bytes;
+use chrono::{DateTime, Local};
+
+use crate::{
+    serial::{Direction, PortEvent},
+    ui::UiEvent,
+};
 
 /// Events sent to the logger via channel.
 pub enum LoggerEvent {
     SerialData(Arc<PortEvent>),
+    /// A marker line such as a fired trigger, written as `--- text ---`
+    Marker {
+        port: Arc<str>,
+        text: String,
+        timestamp: DateTime<Local>,
+    },
     Purge,
 }
 
@@ -19,28 +33,102 @@ pub enum LoggerEvent {
 pub struct Logger {
     log_rx: mpsc::Receiver<LoggerEvent>,
     ui_tx: mpsc::Sender<UiEvent>,
+    /// Directory holding the log files
+    log_dir: PathBuf,
     super_file: File,
     port_files: HashMap<Arc<str>, File>,
+    /// Ports whose last line in their own log is still open (partial)
+    open_lines: HashSet<Arc<str>>,
+    /// super.log line state
+    super_format: SuperLogFormat,
+}
+
+/// Writes events in the super.log format, `[HH:MM:SS.mmm] [port] text`,
+/// with sent data as `[HH:MM:SS.mmm] [port] >> text`.
+///
+/// A partial event leaves its line open so the port's next event can be
+/// appended to it. That only works while no other port, and nothing sent,
+/// came in between, otherwise the open line is ended and the continuation
+/// gets a line of its own.
+#[derive(Default)]
+pub struct SuperLogFormat {
+    /// Port whose partial line is the last, still open line
+    open_line: Option<Arc<str>>,
+}
+
+impl SuperLogFormat {
+    /// Writes one event to `out`.
+    pub fn write(&mut self, out: &mut impl Write, event: &PortEvent) -> io::Result<()> {
+        let PortEvent {
+            port,
+            data,
+            timestamp,
+            partial,
+            direction,
+        } = event;
+
+        let ts = timestamp.format("%H:%M:%S%.3f");
+        let text = String::from_utf8_lossy(data);
+        let text = text.trim_end_matches(['\n', '\r']);
+        let end = if *partial { "" } else { "\n" };
+        let dir = direction.marker();
+
+        match self.open_line.take() {
+            Some(open) if open == *port && *direction == Direction::Rx => {
+                write!(out, "{text}{end}")?
+            }
+            Some(_) => write!(out, "\n[{ts}] [{port}] {dir}{text}{end}")?,
+            None => write!(out, "[{ts}] [{port}] {dir}{text}{end}")?,
+        }
+        if *partial {
+            self.open_line = Some(port.clone());
+        }
+        Ok(())
+    }
+
+    /// Ends the open line, if any, so the next event starts a new one.
+    pub fn end_line(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.open_line.take().is_some() {
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Forgets the open line, e.g. after the output was truncated.
+    pub fn reset(&mut self) {
+        self.open_line = None;
+    }
 }
 
 impl Logger {
-    /// Creates a new logger, setting up the logs directory and super.log file.
+    /// Creates a new logger, setting up the log directory and super.log file.
     /// Returns `None` if setup fails (notifies the UI).
-    pub fn new(log_rx: mpsc::Receiver<LoggerEvent>, ui_tx: mpsc::Sender<UiEvent>) -> Option<Self> {
-        if let Err(e) = fs::create_dir_all("logs") {
+    pub fn new(
+        log_rx: mpsc::Receiver<LoggerEvent>,
+        ui_tx: mpsc::Sender<UiEvent>,
+        log_dir: PathBuf,
+    ) -> Option<Self> {
+        if let Err(e) = fs::create_dir_all(&log_dir) {
             let _ = ui_tx.send(UiEvent::ShowNotification(
-                format!("Logger: failed to create logs/ directory: {e}").into(),
+                format!(
+                    "Logger: failed to create {} directory: {e}",
+                    log_dir.display()
+                )
+                .into(),
             ));
             return None;
         }
 
-        let super_file = Self::open_log("logs/super.log", &ui_tx)?;
+        let super_file = Self::open_log(&log_dir.join("super.log"), &ui_tx)?;
 
         Some(Self {
             log_rx,
             ui_tx,
+            log_dir,
             super_file,
             port_files: HashMap::new(),
+            open_lines: HashSet::new(),
+            super_format: SuperLogFormat::default(),
         })
     }
 
@@ -50,11 +138,18 @@ impl Logger {
             match event {
                 LoggerEvent::Purge => self.purge(),
                 LoggerEvent::SerialData(data) => self.handle_data(&data),
+                LoggerEvent::Marker {
+                    port,
+                    text,
+                    timestamp,
+                } => self.handle_marker(port, &text, timestamp),
             }
         }
     }
 
     fn purge(&mut self) {
+        self.open_lines.clear();
+        self.super_format.reset();
         let _ = self.super_file.set_len(0);
         let _ = self.super_file.rewind();
         for file in self.port_files.values_mut() {
@@ -66,44 +161,114 @@ impl Logger {
             .send(UiEvent::ShowNotification("Logs purged.".into()));
     }
 
+    /// Writes one event to the port's log and super.log.
+    ///
+    /// A partial event leaves its line open; the port's next received event
+    /// is appended to it without a new timestamp. Sent data is marked `>>`.
     fn handle_data(&mut self, event: &PortEvent) {
         let PortEvent {
             port,
             data,
             timestamp,
+            partial,
+            direction,
         } = event;
 
         let ts = timestamp.format("%H:%M:%S%.3f");
         let text = String::from_utf8_lossy(data);
         let text = text.trim_end_matches(['\n', '\r']);
+        let end = if *partial { "" } else { "\n" };
+        let dir = direction.marker();
 
         // Write to per-port file
         if let std::collections::hash_map::Entry::Vacant(entry) =
             self.port_files.entry(port.clone())
+            && let Some(f) = Self::open_log(&self.log_dir.join(format!("{port}.log")), &self.ui_tx)
         {
-            if let Some(f) = Self::open_log(&format!("logs/{port}.log"), &self.ui_tx) {
-                entry.insert(f);
-            }
+            entry.insert(f);
         }
 
+        let continued = self.open_lines.remove(port);
         if let Some(f) = self.port_files.get_mut(port) {
-            let _ = writeln!(f, "[{ts}] {text}");
+            let _ = match (continued, direction) {
+                (true, Direction::Rx) => write!(f, "{text}{end}"),
+                // Sent data ends the open line
+                (true, Direction::Tx) => write!(f, "\n[{ts}] {dir}{text}{end}"),
+                (false, _) => write!(f, "[{ts}] {dir}{text}{end}"),
+            };
+        }
+        if *partial {
+            self.open_lines.insert(port.clone());
         }
 
         // Write to super.log
-        let _ = writeln!(self.super_file, "[{ts}] [{port}] {text}");
+        let _ = self.super_format.write(&mut self.super_file, event);
     }
 
-    fn open_log(path: &str, ui_tx: &mpsc::Sender<UiEvent>) -> Option<File> {
+    /// Writes a marker on its own line in the port's log and super.log,
+    /// ending the port's open line first.
+    fn handle_marker(&mut self, port: Arc<str>, text: &str, timestamp: DateTime<Local>) {
+        if self.open_lines.remove(&port)
+            && let Some(f) = self.port_files.get_mut(&port)
+        {
+            let _ = writeln!(f);
+        }
+        let _ = self.super_format.end_line(&mut self.super_file);
+        self.handle_data(&PortEvent {
+            port,
+            data: Bytes::from(format!("--- {text} ---\n")),
+            timestamp,
+            partial: false,
+            direction: Direction::Rx,
+        });
+    }
+
+    fn open_log(path: &Path, ui_tx: &mpsc::Sender<UiEvent>) -> Option<File> {
         OpenOptions::new()
             .create(true)
             .append(true)
             .open(path)
             .map_err(|e| {
                 ui_tx.send(UiEvent::ShowNotification(
-                    format!("Logger: failed to open {path}: {e}").into(),
+                    format!("Logger: failed to open {}: {e}", path.display()).into(),
                 ))
             })
             .ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_super_log_format() {
+        let mut format = SuperLogFormat::default();
+        let mut out = Vec::new();
+        for event in [
+            PortEvent {
+                partial: true,
+                ..PortEvent::rx("dut", b"login: ")
+            },
+            PortEvent {
+                direction: Direction::Tx,
+                ..PortEvent::rx("dut", b"root\n")
+            },
+            PortEvent {
+                partial: true,
+                ..PortEvent::rx("dut", b"Pass")
+            },
+            PortEvent::rx("dut", b"word:\n"),
+        ] {
+            format.write(&mut out, &event).unwrap();
+        }
+
+        // Drop the timestamps
+        let lines: Vec<String> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| line.split_once(' ').unwrap().1.to_string())
+            .collect();
+        assert_eq!(lines, ["[dut] login: ", "[dut] >> root", "[dut] Password:"]);
+    }
+}
@@ -0,0 +1,55 @@
+//! Plays macros on background threads.
+//!
+//! The runner never touches the ports itself: each send goes back to the
+//! UI as `UiEvent::Send`, so the hub stays owned by the UI loop and a
+//! macro with long delays never blocks it.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{config::macros::Macro, types::sleep::sleep_interruptible, ui::UiEvent};
+
+/// Starts playing `mac` on a new thread.
+///
+/// Steps without their own `ports` go to `ports`. Returns a flag that
+/// stops the macro at the next step or delay slice when set, and the
+/// thread. Sends `UiEvent::MacroFinished` when done, stopped or not.
+pub fn spawn(
+    name: Arc<str>,
+    mac: Macro,
+    ports: Vec<Arc<str>>,
+    ui_tx: Sender<UiEvent>,
+) -> (Arc<AtomicBool>, JoinHandle<()>) {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let stop = cancel.clone();
+
+    let thread = thread::spawn(move || {
+        for step in mac.steps {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            if let Some(data) = step.data {
+                let event = UiEvent::Send {
+                    ports: step.ports.unwrap_or_else(|| ports.clone()),
+                    data,
+                    line_ending: step.line_ending,
+                };
+                if ui_tx.send(event).is_err() {
+                    return;
+                }
+            }
+            if !sleep_interruptible(step.delay, &stop) {
+                break;
+            }
+        }
+        let _ = ui_tx.send(UiEvent::MacroFinished(name));
+    });
+
+    (cancel, thread)
+}
@@ -1,20 +1,31 @@
 //! SerialTUI entry point.
 
+mod cli;
 mod config;
 mod error;
+mod headless;
 mod logger;
+mod macro_runner;
+mod script;
 mod serial;
 mod types;
 mod ui;
 
-use std::sync::mpsc;
+use std::{process::ExitCode, sync::mpsc};
 
 use anyhow::Result;
+use clap::Parser;
 
-use crate::{logger::Logger, serial::hub::SerialHub, ui::Ui};
+use crate::{cli::Cli, logger::Logger, serial::hub::SerialHub, ui::Ui};
 
-fn main() -> Result<()> {
-    let config_path = config::ensure_config();
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    if cli.list_ports {
+        cli::list_ports()?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let config_path = config::ensure_config(cli.config);
 
     // Create channels
 
@@ -23,18 +34,49 @@ fn main() -> Result<()> {
 
     // Start serial hub
     let mut hub = SerialHub::new(ui_tx.clone(), log_tx.clone());
-    hub.load_config(config_path).unwrap_or_else(|e| {
+    hub.load_config(&config_path).unwrap_or_else(|e| {
         let _ = ui_tx.send(ui::UiEvent::ShowNotification(format!("{e}").into()));
     });
 
+    // Ad-hoc ports from the command line
+    for (name, port_config) in cli.ports {
+        hub.open_cli_port(name, port_config);
+    }
+
+    // Reload ports when the config file changes
+    if !cli.headless {
+        let ui_tx = ui_tx.clone();
+        config::spawn_watcher(config_path.clone(), move || {
+            ui_tx.send(ui::UiEvent::ConfigChanged).is_ok()
+        });
+    }
+
     // Start Logger
-    if let Some(logger) = Logger::new(log_rx, ui_tx) {
-        std::thread::spawn(move || logger.run());
+    let logger = if !cli.no_log
+        && let Some(logger) = Logger::new(log_rx, ui_tx.clone(), cli.log_dir)
+    {
+        Some(std::thread::spawn(move || logger.run()))
+    } else {
+        None
+    };
+
+    if cli.headless {
+        let passed = headless::run(hub, ui_tx, ui_rx, &cli.run, cli.junit.as_deref())?;
+        // Let the logger write out everything before exiting
+        drop(log_tx);
+        if let Some(logger) = logger {
+            let _ = logger.join();
+        }
+        return Ok(if passed {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
     }
 
     // UI will own the serial hub
-    let mut ui = Ui::new(hub, ui_rx, log_tx.clone());
+    let mut ui = Ui::new(hub, ui_tx, ui_rx, log_tx.clone(), config_path);
     ui.run()?;
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
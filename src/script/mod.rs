@@ -0,0 +1,95 @@
+//! Rhai scripts for automated expect/send test sequences.
+//!
+//! A script is a [Rhai](https://rhai.rs) program run by a `runner::Runner`,
+//! which adds functions to talk to the ports:
+//!
+//! ```text
+//! // Boot the board, log in and check the firmware version
+//! send("dut", "");                          // text plus tx_line_ending
+//! try {
+//!     expect("dut", "login:", 30000);       // regex, timeout in ms
+//! } catch {
+//!     fail("no login prompt");
+//! }
+//! send("dut", "root");
+//! let m = expect("dut", `version (?<ver>\S+)`);
+//! log(`firmware ${m.ver}`);
+//! if m.ver != "1.2.0" {
+//!     write("dut", "\x03");                 // no line ending
+//!     sleep(500);
+//!     fail(`unexpected firmware ${m.ver}`);
+//! }
+//! pass(`booted ${m.ver}`);
+//! ```
+//!
+//! `expect` returns its capture groups as a map keyed by `"0"`, `"1"`, ...
+//! and by name; it throws on timeout (10 s by default). `send` and `write`
+//! take a string, decoded with `types::escape::unescape` like macro and
+//! input bar text, or a blob of raw bytes. `print` works like `log`. A script that ends
+//! without `pass` or `fail` passes; an uncaught error fails it.
+
+pub mod runner;
+
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rhai::Engine;
+
+use crate::error::ScriptError;
+
+/// A syntax-checked script, ready to run.
+#[derive(Debug)]
+pub struct Script {
+    source: String,
+}
+
+impl Script {
+    /// Reads and parses a script file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("failed to read script: {}", path.display()))?;
+        Self::parse(&source).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    /// Checks the syntax of a script.
+    ///
+    /// Unknown functions and variables are only reported when run.
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        Engine::new_raw()
+            .compile(source)
+            .map_err(|err| ScriptError::Parse {
+                line: err.1.line().unwrap_or(0),
+                message: err.0.to_string(),
+            })?;
+        Ok(Self {
+            source: source.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let script = Script::parse(
+            r#"
+            // comment
+            send("dut", "root");
+            let m = expect("dut", `version (\d+)`, 5000);
+            if m["1"] == "2" { pass() }
+            "#,
+        );
+        assert!(script.is_ok());
+
+        let line = |source| match Script::parse(source) {
+            Err(ScriptError::Parse { line, .. }) => line,
+            Ok(_) => 0,
+        };
+        assert_eq!(line("sleep(10);\nsend(\"dut\""), 2);
+        assert_eq!(line("let x = ;"), 1);
+        assert_eq!(line("log(\"open);"), 1);
+        assert_eq!(line("undefined_fn();"), 0);
+    }
+}
@@ -0,0 +1,482 @@
+//! Runs a `Script` against the `PortEvent` stream.
+//!
+//! The runner owns no ports: sends and log lines are handed to a `report`
+//! callback. `spawn` runs a script on its own thread and reports through
+//! `UiEvent`s, leaving the sends to whoever owns the hub.
+//!
+//! Received data is read from a `SerialHub::subscribe` receiver into a
+//! per-port buffer that `expect` searches and consumes up to the end of
+//! each match.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, RecvTimeoutError, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use regex::Regex;
+use rhai::{Blob, Dynamic, Engine, EvalAltResult, Map, NativeCallContext, Position};
+
+use crate::{
+    serial::PortEvent,
+    types::{
+        escape,
+        sleep::{SLICE, sleep_interruptible},
+    },
+    ui::UiEvent,
+};
+
+use super::Script;
+
+/// Most unmatched bytes kept per port; older data is dropped.
+const MAX_BUFFER: usize = 64 * 1024;
+
+/// Timeout of an `expect` without an explicit one.
+const DEFAULT_EXPECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+type RhaiResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Side effects of a running script.
+pub enum ScriptEvent {
+    /// Send to one port; `line_ending` appends its `tx_line_ending`
+    Send {
+        port: Arc<str>,
+        data: Bytes,
+        line_ending: bool,
+    },
+    /// Calling a port function on this 1-based source line
+    Progress(usize),
+    /// A `log` or `print` call
+    Log(String),
+}
+
+/// How a script ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Passed(String),
+    Failed(String),
+    /// Stopped through the stop flag
+    Stopped,
+}
+
+/// Starts `script` on a new thread, reading port data from `events`.
+///
+/// Sends become `UiEvent::Send`, progress and log lines
+/// `UiEvent::ScriptProgress` / `ScriptLog`, and the result is sent as
+/// `UiEvent::ScriptFinished`. Returns the stop flag and the thread.
+pub fn spawn(
+    script: Script,
+    events: Receiver<Arc<PortEvent>>,
+    ui_tx: Sender<UiEvent>,
+) -> (Arc<AtomicBool>, JoinHandle<()>) {
+    let stop = Arc::new(AtomicBool::new(false));
+    let runner = Runner::new(events, stop.clone());
+
+    let thread = thread::spawn(move || {
+        let report_tx = ui_tx.clone();
+        let outcome = runner.run(&script, move |event| {
+            let _ = report_tx.send(match event {
+                ScriptEvent::Send {
+                    port,
+                    data,
+                    line_ending,
+                } => UiEvent::Send {
+                    ports: vec![port],
+                    data,
+                    line_ending,
+                },
+                ScriptEvent::Progress(line) => UiEvent::ScriptProgress(line),
+                ScriptEvent::Log(message) => UiEvent::ScriptLog(message.into()),
+            });
+        });
+        let _ = ui_tx.send(UiEvent::ScriptFinished(outcome));
+    });
+
+    (stop, thread)
+}
+
+/// Runs a script to completion.
+pub struct Runner {
+    events: Receiver<Arc<PortEvent>>,
+    stop: Arc<AtomicBool>,
+    /// Received, not yet matched text per port
+    buffers: HashMap<Arc<str>, String>,
+}
+
+/// What the script functions share while a script runs.
+struct State {
+    runner: Runner,
+    report: Box<dyn FnMut(ScriptEvent)>,
+    /// Set by `pass` and `fail`
+    outcome: Option<Outcome>,
+}
+
+impl Runner {
+    pub fn new(events: Receiver<Arc<PortEvent>>, stop: Arc<AtomicBool>) -> Self {
+        Self {
+            events,
+            stop,
+            buffers: HashMap::new(),
+        }
+    }
+
+    /// Runs `script`, blocking until it passes, fails or is stopped.
+    ///
+    /// Sends, progress and log lines go to `report` as they happen.
+    pub fn run(self, script: &Script, report: impl FnMut(ScriptEvent) + 'static) -> Outcome {
+        let stop = self.stop.clone();
+        let state = Rc::new(RefCell::new(State {
+            runner: self,
+            report: Box::new(report),
+            outcome: None,
+        }));
+
+        let mut engine = Engine::new();
+        // Also interrupts scripts that loop without calling a port function
+        engine.on_progress(move |_| stop.load(Ordering::Relaxed).then_some(Dynamic::UNIT));
+        register(&mut engine, &state);
+        let result = engine.run(&script.source);
+
+        if let Some(outcome) = state.borrow_mut().outcome.take() {
+            return outcome;
+        }
+        match result {
+            Ok(()) => Outcome::Passed(String::new()),
+            Err(err) => match innermost(*err) {
+                EvalAltResult::ErrorTerminated(..) => Outcome::Stopped,
+                err => Outcome::Failed(describe(err)),
+            },
+        }
+    }
+
+    /// Buffers received data until `done` returns true or `deadline` passes.
+    ///
+    /// Returns true if `done` was satisfied. A stop request ends the wait
+    /// early.
+    fn wait(
+        &mut self,
+        deadline: Instant,
+        mut done: impl FnMut(&mut HashMap<Arc<str>, String>) -> bool,
+    ) -> bool {
+        loop {
+            if done(&mut self.buffers) {
+                return true;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || self.stopped() {
+                return false;
+            }
+
+            match self.events.recv_timeout(remaining.min(SLICE)) {
+                Ok(event) => self.buffer(&event),
+                Err(RecvTimeoutError::Timeout) => {}
+                // No more data will come, but timeouts still apply
+                Err(RecvTimeoutError::Disconnected) => {
+                    sleep_interruptible(remaining, &self.stop);
+                }
+            }
+        }
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Appends received data to its port's buffer, dropping the oldest
+    /// text beyond `MAX_BUFFER`.
+    fn buffer(&mut self, event: &PortEvent) {
+        let buffer = self.buffers.entry(event.port.clone()).or_default();
+        buffer.push_str(&String::from_utf8_lossy(&event.data));
+
+        if buffer.len() > MAX_BUFFER {
+            let mut cut = buffer.len() - MAX_BUFFER;
+            while !buffer.is_char_boundary(cut) {
+                cut += 1;
+            }
+            buffer.drain(..cut);
+        }
+    }
+}
+
+impl State {
+    /// Reports the line of the script function being called.
+    fn progress(&mut self, ctx: &NativeCallContext) {
+        if let Some(line) = ctx.call_position().line() {
+            (self.report)(ScriptEvent::Progress(line));
+        }
+    }
+
+    fn send(&mut self, ctx: &NativeCallContext, port: &str, data: Bytes, line_ending: bool) {
+        self.progress(ctx);
+        (self.report)(ScriptEvent::Send {
+            port: port.into(),
+            data,
+            line_ending,
+        });
+    }
+
+    /// Waits for `pattern` to match data received on `port`.
+    ///
+    /// On a match the buffer is consumed up to the match end and the
+    /// captures are returned. Throws on timeout.
+    fn expect(
+        &mut self,
+        ctx: &NativeCallContext,
+        port: &str,
+        pattern: &str,
+        timeout: Duration,
+    ) -> RhaiResult<Map> {
+        self.progress(ctx);
+        let pos = ctx.call_position();
+        let regex = Regex::new(pattern).map_err(|e| runtime_error(e.to_string(), pos))?;
+
+        let mut captures = Map::new();
+        let matched = self.runner.wait(Instant::now() + timeout, |buffers| {
+            let Some(buffer) = buffers.get_mut(port) else {
+                return false;
+            };
+            let Some(caps) = regex.captures(buffer) else {
+                return false;
+            };
+
+            let text =
+                |m: Option<regex::Match>| Dynamic::from(m.map_or("", |m| m.as_str()).to_string());
+            for (i, m) in caps.iter().enumerate() {
+                captures.insert(i.to_string().into(), text(m));
+            }
+            for name in regex.capture_names().flatten() {
+                captures.insert(name.into(), text(caps.name(name)));
+            }
+            let end = caps.get(0).map_or(0, |m| m.end());
+
+            buffer.drain(..end);
+            true
+        });
+
+        if matched {
+            Ok(captures)
+        } else if self.runner.stopped() {
+            Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, pos).into())
+        } else {
+            Err(runtime_error(
+                format!(
+                    "timed out after {}ms waiting for /{regex}/ on {port}",
+                    timeout.as_millis()
+                ),
+                pos,
+            ))
+        }
+    }
+
+    fn sleep(&mut self, ctx: &NativeCallContext, ms: i64) -> RhaiResult<()> {
+        self.progress(ctx);
+        self.runner.wait(Instant::now() + millis(ms), |_| false);
+        if self.runner.stopped() {
+            return Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, ctx.call_position()).into());
+        }
+        Ok(())
+    }
+
+    fn log(&mut self, message: &str) {
+        (self.report)(ScriptEvent::Log(message.to_string()));
+    }
+
+    /// Ends the script with `outcome`; `try` cannot catch this.
+    fn end(&mut self, ctx: &NativeCallContext, passed: bool, message: &str) -> RhaiResult<()> {
+        let pos = ctx.call_position();
+        self.outcome = Some(if passed {
+            Outcome::Passed(message.to_string())
+        } else {
+            Outcome::Failed(describe(EvalAltResult::ErrorRuntime(message.into(), pos)))
+        });
+        Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, pos).into())
+    }
+}
+
+/// Adds the port functions, backed by `state`, to `engine`.
+fn register(engine: &mut Engine, state: &Rc<RefCell<State>>) {
+    for (name, line_ending) in [("send", true), ("write", false)] {
+        let s = state.clone();
+        engine.register_fn(
+            name,
+            move |ctx: NativeCallContext, port: &str, text: &str| -> RhaiResult<()> {
+                let data = escape::unescape(text)
+                    .map_err(|e| runtime_error(e.to_string(), ctx.call_position()))?;
+                s.borrow_mut()
+                    .send(&ctx, port, Bytes::from(data), line_ending);
+                Ok(())
+            },
+        );
+        let s = state.clone();
+        engine.register_fn(
+            name,
+            move |ctx: NativeCallContext, port: &str, data: Blob| {
+                s.borrow_mut()
+                    .send(&ctx, port, Bytes::from(data), line_ending);
+            },
+        );
+    }
+
+    let s = state.clone();
+    engine.register_fn(
+        "expect",
+        move |ctx: NativeCallContext, port: &str, regex: &str| {
+            s.borrow_mut()
+                .expect(&ctx, port, regex, DEFAULT_EXPECT_TIMEOUT)
+        },
+    );
+    let s = state.clone();
+    engine.register_fn(
+        "expect",
+        move |ctx: NativeCallContext, port: &str, regex: &str, timeout: i64| {
+            s.borrow_mut().expect(&ctx, port, regex, millis(timeout))
+        },
+    );
+
+    let s = state.clone();
+    engine.register_fn("sleep", move |ctx: NativeCallContext, ms: i64| {
+        s.borrow_mut().sleep(&ctx, ms)
+    });
+
+    let s = state.clone();
+    engine.register_fn("log", move |message: &str| s.borrow_mut().log(message));
+    let s = state.clone();
+    engine.on_print(move |message| s.borrow_mut().log(message));
+    let s = state.clone();
+    engine.on_debug(move |message, _, _| s.borrow_mut().log(message));
+
+    for (name, passed) in [("pass", true), ("fail", false)] {
+        let s = state.clone();
+        engine.register_fn(name, move |ctx: NativeCallContext| {
+            s.borrow_mut().end(&ctx, passed, "")
+        });
+        let s = state.clone();
+        engine.register_fn(name, move |ctx: NativeCallContext, message: &str| {
+            s.borrow_mut().end(&ctx, passed, message)
+        });
+    }
+}
+
+/// Milliseconds from a script, negative ones count as zero.
+fn millis(ms: i64) -> Duration {
+    Duration::from_millis(ms.max(0) as u64)
+}
+
+fn runtime_error(message: String, pos: Position) -> Box<EvalAltResult> {
+    EvalAltResult::ErrorRuntime(message.into(), pos).into()
+}
+
+/// Strips the call-stack wrappers of an error raised inside a function.
+fn innermost(err: EvalAltResult) -> EvalAltResult {
+    match err {
+        EvalAltResult::ErrorInFunctionCall(.., inner, _)
+        | EvalAltResult::ErrorInModule(.., inner, _) => innermost(*inner),
+        err => err,
+    }
+}
+
+/// Formats a script error as `line N: message`.
+fn describe(mut err: EvalAltResult) -> String {
+    let line = err.take_position().line();
+    let message = match err {
+        // Thrown values and `fail` messages read better without a prefix
+        EvalAltResult::ErrorRuntime(value, _) => value.to_string(),
+        err => err.to_string(),
+    };
+    match line {
+        Some(line) => format!("line {line}: {message}"),
+        None => message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let script = Script::parse(
+            r#"
+            send("dut", "ver");
+            let m = expect("dut", `version (?<ver>\d+)\.(\d+)`, 1000);
+            log(`v${m.ver}.${m["2"]}`);
+            if m.ver == "2" {
+                try {
+                    expect("dut", "never", 10);
+                    fail("unreachable");
+                } catch (err) {
+                    print(err);
+                }
+                write("dut", blob(1, 3));
+                try {
+                    write("dut", `\e`);
+                } catch (err) {
+                    print(err);
+                }
+                write("dut", `\\r\x1b[A\r`);
+                expect("other", "ok", 10);
+            }
+            pass(`old ${m.ver}`);
+            "#,
+        )
+        .unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        tx.send(Arc::new(PortEvent::rx("other", b"version 9.9\n")))
+            .unwrap();
+        tx.send(Arc::new(PortEvent::rx("dut", b"boot\nversion 2.")))
+            .unwrap();
+        tx.send(Arc::new(PortEvent::rx("dut", b"7\n"))).unwrap();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (report_tx, report_rx) = mpsc::channel();
+        let outcome = Runner::new(rx, stop).run(&script, move |event| {
+            report_tx.send(event).unwrap();
+        });
+
+        let mut sent = Vec::new();
+        let mut logs = Vec::new();
+        for event in report_rx.try_iter() {
+            match event {
+                ScriptEvent::Send {
+                    port,
+                    data,
+                    line_ending,
+                } => sent.push((port, data, line_ending)),
+                ScriptEvent::Log(message) => logs.push(message),
+                ScriptEvent::Progress(_) => {}
+            }
+        }
+
+        assert_eq!(
+            sent,
+            [
+                ("dut".into(), Bytes::from_static(b"ver"), true),
+                ("dut".into(), Bytes::from_static(b"\x03"), false),
+                ("dut".into(), Bytes::from_static(b"\\r\x1b[A\r"), false),
+            ]
+        );
+        assert_eq!(
+            logs,
+            [
+                "v2.7",
+                "timed out after 10ms waiting for /never/ on dut",
+                r"invalid escape sequence: \e (expected \r, \n, \t, \0, \\ or \xHH)",
+            ]
+        );
+        assert_eq!(
+            outcome,
+            Outcome::Failed("line 19: timed out after 10ms waiting for /ok/ on other".into())
+        );
+    }
+}
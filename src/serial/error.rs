@@ -2,17 +2,24 @@
 
 use std::sync::{Arc, mpsc::TrySendError};
 
-use bytes::Bytes;
 use thiserror::Error;
 
+use super::port::WriterCommand;
+
 #[derive(Debug, Error)]
 pub enum SerialError {
     #[error("port not found: {0}")]
     PortNotFound(Arc<str>),
 
+    #[error("port not connected: {0}")]
+    NotConnected(Arc<str>),
+
+    #[error("no reset_sequence configured: {0}")]
+    NoResetSequence(Arc<str>),
+
     #[error("failed to open port: {0}")]
     Open(#[from] serialport::Error),
 
     #[error("write channel full or closed")]
-    TrySend(#[from] TrySendError<Bytes>),
+    TrySend(#[from] TrySendError<WriterCommand>),
 }
@@ -1,7 +1,7 @@
 //! Central hub for multiple serial port connections.
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fs::read_to_string,
     path::Path,
     sync::{Arc, mpsc},
@@ -10,63 +10,293 @@ use std::{
 use anyhow::{Context, Result};
 use bytes::Bytes;
 
-use crate::{config::PortConfig, logger::LoggerEvent, ui::UiEvent};
+use crate::{
+    config::{
+        PortConfig,
+        port::ControlSequence,
+        triggers::{TRIGGERS_KEY, Trigger},
+    },
+    logger::LoggerEvent,
+    ui::UiEvent,
+};
+
+use super::{
+    ConnectionState, PortEvent, SerialError,
+    port::{ActiveTrigger, Port, SharedTriggers, Subscribers, WriterCommand},
+    stats::{PortStats, SharedStats},
+};
 
-use super::{SerialError, port::Port};
+/// A port added to or removed from the hub by `SerialHub::reload_config`.
+///
+/// Changed sections keep their name and are only reported as notifications.
+pub enum ConfigChange {
+    /// New section; the port was opened
+    Added(Arc<str>),
+    /// Section removed; the port was closed and dropped
+    Removed(Arc<str>),
+}
 
 /// Manages multiple serial port connections.
+///
+/// Ports are kept sorted by name so list indices stay stable while
+/// ports are opened and closed at runtime.
 pub struct SerialHub {
-    ports: HashMap<Arc<str>, Port>,
+    ports: BTreeMap<Arc<str>, Port>,
+    /// Sections from the last config load, so a reload only touches ports
+    /// whose section changed in the file (not ones added or edited at
+    /// runtime)
+    file_configs: BTreeMap<Arc<str>, PortConfig>,
+    /// Ports from `--port`, left out of reloads even if a section has the
+    /// same name
+    cli_names: BTreeSet<Arc<str>>,
     ui_tx: mpsc::Sender<UiEvent>,
     log_tx: mpsc::Sender<LoggerEvent>,
+    /// Receivers of every port's data, see `subscribe`
+    subscribers: Subscribers,
+    /// `[[triggers]]` from the config, checked by every reader
+    triggers: SharedTriggers,
 }
 
 impl SerialHub {
     /// Creates a new hub and returns the event receiver for the port data
     pub fn new(ui_tx: mpsc::Sender<UiEvent>, log_tx: mpsc::Sender<LoggerEvent>) -> Self {
         Self {
-            ports: HashMap::new(),
+            ports: BTreeMap::new(),
+            file_configs: BTreeMap::new(),
+            cli_names: BTreeSet::new(),
             ui_tx,
             log_tx,
+            subscribers: Subscribers::default(),
+            triggers: SharedTriggers::default(),
         }
     }
 
-    /// Loads and opens all ports from a TOML config file.
-    pub fn load_config(&mut self, path: impl AsRef<Path>) -> Result<()> {
-        let path = path.as_ref();
+    /// Returns a receiver for the data of all ports, opened now or later.
+    ///
+    /// Gets the same `PortEvent`s as the logger. Dropping the receiver
+    /// unsubscribes.
+    pub fn subscribe(&self) -> mpsc::Receiver<Arc<PortEvent>> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Reads and parses a TOML config file into port sections and triggers.
+    fn read_config(path: &Path) -> Result<(HashMap<String, PortConfig>, Vec<Trigger>)> {
         let content = read_to_string(path)
             .with_context(|| format!("failed to read config: {}", path.display()))?;
 
-        let ports: HashMap<String, PortConfig> =
-            toml::from_str(&content).context("failed to parse config")?;
+        let mut table: toml::Table = toml::from_str(&content).context("failed to parse config")?;
+        let triggers = match table.remove(TRIGGERS_KEY) {
+            Some(triggers) => triggers.try_into().context("failed to parse triggers")?,
+            None => Vec::new(),
+        };
+        let ports = table.try_into().context("failed to parse config")?;
+        Ok((ports, triggers))
+    }
+
+    /// Loads and opens all ports from a TOML config file.
+    pub fn load_config(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let (ports, triggers) = Self::read_config(path.as_ref())?;
+        *self.triggers.write().unwrap() = triggers.into_iter().map(ActiveTrigger::from).collect();
 
         for (name, config) in ports {
-            if let Err(e) = self.open(name.clone(), config) {
-                let _ = self.ui_tx.send(UiEvent::ShowNotification(
-                    format!("failed to open port {name}: {e}").into(),
-                ));
-            }
+            self.file_configs
+                .insert(name.as_str().into(), config.clone());
+            self.open_or_list(name, config);
         }
         Ok(())
     }
 
+    /// Opens an ad-hoc `--port`, replacing a config section of the same name.
+    ///
+    /// A port that fails to open stays listed as closed. Config reloads
+    /// leave it alone.
+    pub fn open_cli_port(&mut self, name: String, config: PortConfig) {
+        self.cli_names.insert(name.as_str().into());
+        self.open_or_list(name, config);
+    }
+
+    /// Re-reads the config file and applies the differences to the hub.
+    ///
+    /// New sections are opened, removed sections closed and changed sections
+    /// reopened; triggers are replaced. Each change is reported as a
+    /// notification. A read or parse error is returned before anything is
+    /// touched.
+    ///
+    /// Only sections that differ from the last load count as changed, so
+    /// unsaved live edits (and renames or removals) of the other ports are
+    /// kept. `--port` ports are never touched.
+    pub fn reload_config(&mut self, path: impl AsRef<Path>) -> Result<Vec<ConfigChange>> {
+        let (ports, triggers) = Self::read_config(path.as_ref())?;
+        *self.triggers.write().unwrap() = triggers.into_iter().map(ActiveTrigger::from).collect();
+        let mut changes = Vec::new();
+
+        let removed: Vec<Arc<str>> = self
+            .file_configs
+            .keys()
+            .filter(|name| !ports.contains_key(name.as_ref()))
+            .cloned()
+            .collect();
+        for name in removed {
+            self.file_configs.remove(&name);
+            if !self.cli_names.contains(&name) && self.remove(&name).is_ok() {
+                self.notify(format!("{name}: removed from config, closed"));
+                changes.push(ConfigChange::Removed(name));
+            }
+        }
+
+        for (name, config) in ports {
+            let name: Arc<str> = name.into();
+            let previous = self.file_configs.insert(name.clone(), config.clone());
+            if previous.as_ref() == Some(&config) || self.cli_names.contains(&name) {
+                continue;
+            }
+
+            match self.ports.get(&name) {
+                None => {
+                    self.open_or_list(name.to_string(), config);
+                    self.notify(format!("{name}: added from config"));
+                    changes.push(ConfigChange::Added(name));
+                }
+                Some(port) if *port.config != config => match self.reconfigure(&name, config) {
+                    Ok(()) => self.notify(format!("{name}: config changed, reopened")),
+                    Err(e) => self.notify(format!("{name}: config changed, reopen failed: {e}")),
+                },
+                Some(_) => {}
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Opens a port, keeping it listed as closed if the device can't be opened.
+    fn open_or_list(&mut self, name: String, config: PortConfig) {
+        if let Err(e) = self.open(name.clone(), config.clone()) {
+            self.notify(format!("failed to open port {name}: {e}"));
+            // Keep it listed so it can be opened from the port list later
+            self.ports
+                .insert(name.into(), Port::closed(config, SharedStats::default()));
+        }
+    }
+
+    fn notify(&self, msg: String) {
+        let _ = self.ui_tx.send(UiEvent::ShowNotification(msg.into()));
+    }
+
     /// Opens a serial port and adds it to the hub.
+    ///
+    /// An existing port with the same name is closed first.
     pub fn open(&mut self, name: String, config: PortConfig) -> Result<(), SerialError> {
         let name: Arc<str> = name.into();
+        if let Some(mut old) = self.ports.remove(&name) {
+            old.close();
+        }
         let port = Port::open(
             name.clone(),
             config,
+            SharedStats::default(),
             self.ui_tx.clone(),
             self.log_tx.clone(),
+            self.subscribers.clone(),
+            self.triggers.clone(),
         )?;
         self.ports.insert(name, port);
         Ok(())
     }
 
+    /// Closes a port, stopping its reader/writer threads.
+    ///
+    /// The port stays listed with its config so it can be reopened.
+    pub fn close(&mut self, name: &str) -> Result<(), SerialError> {
+        let port = self
+            .ports
+            .get_mut(name)
+            .ok_or_else(|| SerialError::PortNotFound(name.into()))?;
+        port.close();
+        let _ = self.ui_tx.send(UiEvent::ConnectionChanged(
+            name.into(),
+            ConnectionState::Closed,
+        ));
+        Ok(())
+    }
+
+    /// Closes a port and removes it from the hub.
+    pub fn remove(&mut self, name: &str) -> Result<(), SerialError> {
+        self.close(name)?;
+        self.ports.remove(name);
+        Ok(())
+    }
+
+    /// Closes and reopens a port with its current config.
+    pub fn reopen(&mut self, name: &str) -> Result<(), SerialError> {
+        let config = self
+            .ports
+            .get(name)
+            .ok_or_else(|| SerialError::PortNotFound(name.into()))?
+            .config
+            .as_ref()
+            .clone();
+        self.reconfigure(name, config)
+    }
+
+    /// Closes a port (if open) and opens it again with `config`.
+    ///
+    /// A listed port keeps its stats, and opening it again counts as a
+    /// reconnect. If the device fails to open, the port stays listed as
+    /// closed with the new config so the settings can be fixed and retried.
+    pub fn reconfigure(&mut self, name: &str, config: PortConfig) -> Result<(), SerialError> {
+        let listed = self.ports.get(name).map(|port| port.stats.clone());
+        if listed.is_some() {
+            self.close(name)?;
+        }
+        let reopened = listed.is_some();
+        let stats = listed.unwrap_or_default();
+
+        match Port::open(
+            name.into(),
+            config.clone(),
+            stats.clone(),
+            self.ui_tx.clone(),
+            self.log_tx.clone(),
+            self.subscribers.clone(),
+            self.triggers.clone(),
+        ) {
+            Ok(port) => {
+                if reopened {
+                    stats.record_reconnect();
+                }
+                self.ports.insert(name.into(), port);
+                let _ = self.ui_tx.send(UiEvent::ConnectionChanged(
+                    name.into(),
+                    ConnectionState::Connected,
+                ));
+                Ok(())
+            }
+            Err(e) => {
+                self.ports.insert(name.into(), Port::closed(config, stats));
+                Err(e)
+            }
+        }
+    }
+
     pub fn get_config(&self, name: &str) -> Option<&Arc<PortConfig>> {
         self.ports.get(name).map(|p| &p.config)
     }
 
+    /// Returns the connection state of a port, if it is open.
+    pub fn port_state(&self, name: &str) -> Option<ConnectionState> {
+        self.ports.get(name).map(|p| p.state())
+    }
+
+    /// Returns a snapshot of every port's traffic and error counters.
+    pub fn list_stats(&self) -> Vec<(Arc<str>, PortStats)> {
+        self.ports
+            .iter()
+            .map(|(name, port)| (name.clone(), port.stats.snapshot()))
+            .collect()
+    }
+
     pub fn list_ports(&self) -> Vec<(Arc<str>, Arc<PortConfig>)> {
         self.ports
             .iter()
@@ -74,20 +304,175 @@ impl SerialHub {
             .collect()
     }
 
-    /// Sends data to one or more ports, appending each port's configured line ending.
+    /// Sends data to one or more ports, appending each port's `tx_line_ending`.
     pub fn send(&self, ports: &[Arc<str>], data: Bytes) -> Result<(), SerialError> {
+        self.send_each(ports, |port| {
+            let ending = port.config.tx_line_ending.as_bytes();
+            let mut buf = Vec::with_capacity(data.len() + ending.len());
+            buf.extend_from_slice(&data);
+            buf.extend_from_slice(ending);
+            Bytes::from(buf)
+        })
+    }
+
+    /// Sends data to one or more ports exactly as given, without a line ending.
+    pub fn send_raw(&self, ports: &[Arc<str>], data: Bytes) -> Result<(), SerialError> {
+        self.send_each(ports, |_| data.clone())
+    }
+
+    /// Runs control line steps (DTR, RTS, BREAK) on one or more ports.
+    pub fn control(
+        &self,
+        ports: &[Arc<str>],
+        sequence: ControlSequence,
+    ) -> Result<(), SerialError> {
+        self.queue_each(ports, |_, _| Ok(WriterCommand::Control(sequence.clone())))
+    }
+
+    /// Runs each port's configured `reset_sequence`.
+    ///
+    /// Fails at the first port without one; the ports before it are reset.
+    pub fn reset(&self, ports: &[Arc<str>]) -> Result<(), SerialError> {
+        self.queue_each(ports, |name, port| {
+            let sequence = &port.config.reset_sequence;
+            if sequence.0.is_empty() {
+                return Err(SerialError::NoResetSequence(name.clone()));
+            }
+            Ok(WriterCommand::Control(sequence.clone()))
+        })
+    }
+
+    /// Queues the bytes built by `frame` on each port, stopping at the first error.
+    fn send_each(
+        &self,
+        ports: &[Arc<str>],
+        frame: impl Fn(&Port) -> Bytes,
+    ) -> Result<(), SerialError> {
+        self.queue_each(ports, |_, port| Ok(WriterCommand::Data(frame(port))))
+    }
+
+    /// Queues the writer command built by `command` on each port, stopping
+    /// at the first error.
+    fn queue_each(
+        &self,
+        ports: &[Arc<str>],
+        command: impl Fn(&Arc<str>, &Port) -> Result<WriterCommand, SerialError>,
+    ) -> Result<(), SerialError> {
         for name in ports {
             let port = self
                 .ports
                 .get(name)
                 .ok_or_else(|| SerialError::PortNotFound(name.clone()))?;
-
-            let ending = port.config.line_ending.as_bytes();
-            let mut buf = Vec::with_capacity(data.len() + ending.len());
-            buf.extend_from_slice(&data);
-            buf.extend_from_slice(ending);
-            port.writer_tx.try_send(Bytes::from(buf))?;
+            port.send(name, command(name, port)?)?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write, time::Duration};
+
+    use serialport::{SerialPort, TTYPort};
+
+    use super::*;
+
+    fn port(baud_rate: u32) -> String {
+        format!("path = \"/dev/serial_tui_missing\"\nbaud_rate = {baud_rate}\n")
+    }
+
+    #[test]
+    fn test_reload_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ports.toml");
+        let (ui_tx, _ui_rx) = mpsc::channel();
+        let (log_tx, _log_rx) = mpsc::channel();
+        let mut hub = SerialHub::new(ui_tx, log_tx);
+        let baud = |hub: &SerialHub, name: &str| hub.get_config(name).map(|c| c.baud_rate);
+
+        fs::write(
+            &path,
+            format!("[com1]\n{}[com2]\n{}", port(9600), port(9600)),
+        )
+        .unwrap();
+        hub.load_config(&path).unwrap();
+        // Missing devices stay listed as closed
+        assert_eq!(baud(&hub, "com2"), Some(9600));
+
+        // Unsaved live edit of com1
+        let edited = PortConfig {
+            baud_rate: 57_600,
+            ..hub.get_config("com1").unwrap().as_ref().clone()
+        };
+        let _ = hub.reconfigure("com1", edited);
+
+        // Only sections changed in the file are applied
+        fs::write(
+            &path,
+            format!(
+                "[com1]\n{}[com2]\n{}[com3]\n{}",
+                port(9600),
+                port(19_200),
+                port(9600)
+            ),
+        )
+        .unwrap();
+        let changes = hub.reload_config(&path).unwrap();
+        assert!(matches!(&changes[..], [ConfigChange::Added(name)] if &**name == "com3"));
+        assert_eq!(baud(&hub, "com1"), Some(57_600));
+        assert_eq!(baud(&hub, "com2"), Some(19_200));
+
+        // A changed section overrides the live edit
+        fs::write(
+            &path,
+            format!("[com1]\n{}[com2]\n{}", port(115_200), port(19_200)),
+        )
+        .unwrap();
+        let changes = hub.reload_config(&path).unwrap();
+        assert!(matches!(&changes[..], [ConfigChange::Removed(name)] if &**name == "com3"));
+        assert_eq!(baud(&hub, "com1"), Some(115_200));
+        assert_eq!(baud(&hub, "com3"), None);
+
+        // A --port override of a section survives changes to it
+        hub.open_cli_port("com2".into(), PortConfig::default());
+        fs::write(
+            &path,
+            format!("[com1]\n{}[com2]\n{}", port(115_200), port(9600)),
+        )
+        .unwrap();
+        assert!(hub.reload_config(&path).unwrap().is_empty());
+        assert_eq!(baud(&hub, "com2"), Some(PortConfig::default().baud_rate));
+        fs::write(&path, format!("[com1]\n{}", port(115_200))).unwrap();
+        assert!(hub.reload_config(&path).unwrap().is_empty());
+        assert!(baud(&hub, "com2").is_some());
+
+        // A broken file leaves the ports alone
+        fs::write(&path, "[com1").unwrap();
+        assert!(hub.reload_config(&path).is_err());
+        assert_eq!(hub.list_ports().len(), 2);
+    }
+
+    #[test]
+    fn test_reopen_keeps_stats() {
+        let (mut device, far_end) = TTYPort::pair().unwrap();
+        let (ui_tx, ui_rx) = mpsc::channel();
+        let (log_tx, _log_rx) = mpsc::channel();
+        let mut hub = SerialHub::new(ui_tx, log_tx);
+        let config = PortConfig {
+            path: far_end.name().unwrap().into(),
+            ..Default::default()
+        };
+        hub.open("dut".into(), config).unwrap();
+
+        device.write_all(b"hello\n").unwrap();
+        while !matches!(
+            ui_rx.recv_timeout(Duration::from_secs(2)).unwrap(),
+            UiEvent::PortData(_)
+        ) {}
+        hub.reopen("dut").unwrap();
+
+        let stats = &hub.list_stats()[0].1;
+        assert_eq!((stats.rx.bytes, stats.rx.lines), (6, 1));
+        assert_eq!(stats.reconnects, 1);
+    }
+}
@@ -3,6 +3,7 @@
 mod error;
 pub mod hub;
 pub mod port;
+pub mod stats;
 
 pub use error::SerialError;
-pub use port::PortEvent;
+pub use port::{ConnectionState, Direction, ModemLines, PortEvent};
@@ -1,151 +1,987 @@
 //! Single port connection with reader/writer threads.
 
 use std::{
-    io::{Read, Write},
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    io::Write,
     sync::{
-        Arc,
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, Ordering},
         mpsc::{self, Receiver, Sender},
     },
-    time::Duration,
+    thread::JoinHandle,
+    time::{Duration, Instant},
 };
 
 use bytes::{Bytes, BytesMut};
 use chrono::{DateTime, Local};
-use memchr::{
-    memchr,
-    memmem::{self},
-};
-use serialport::SerialPort;
+use serialport::{SerialPort, SerialPortType};
 
 use crate::{
-    config::{PortConfig, port::LineEnding},
+    config::{
+        PortConfig,
+        port::{ControlSequence, ControlStep},
+        triggers::{Trigger, TriggerAction},
+    },
     logger::LoggerEvent,
-    serial::SerialError,
+    serial::{SerialError, stats::SharedStats},
+    types::sleep::sleep_interruptible,
     ui::UiEvent,
 };
 
-/// Event emitted when a serial port receives data.
+/// Describes how a system device is attached, e.g. `USB 0403:6001  FT232R  SN A10K`.
+pub fn device_details(port_type: &SerialPortType) -> String {
+    match port_type {
+        SerialPortType::UsbPort(usb) => {
+            let mut details = format!("USB {:04x}:{:04x}", usb.vid, usb.pid);
+            if let Some(product) = usb.product.as_ref().or(usb.manufacturer.as_ref()) {
+                details.push_str(&format!("  {product}"));
+            }
+            if let Some(serial) = &usb.serial_number {
+                details.push_str(&format!("  SN {serial}"));
+            }
+            details
+        }
+        SerialPortType::PciPort => "PCI".into(),
+        SerialPortType::BluetoothPort => "Bluetooth".into(),
+        SerialPortType::Unknown => String::new(),
+    }
+}
+
+/// How often the reader thread polls the modem status lines.
+const MODEM_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Which way data went over the wire.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    /// Received from the device
+    Rx,
+    /// Written to the device (local echo)
+    Tx,
+}
+
+impl Direction {
+    /// Marker put before the data in the display and logs.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Direction::Rx => "",
+            Direction::Tx => ">> ",
+        }
+    }
+}
+
+/// Event emitted when a serial port receives data, or sent data is echoed.
 pub struct PortEvent {
     pub port: Arc<str>,
     pub data: Bytes,
     pub timestamp: DateTime<Local>,
+    /// Data has no line ending yet (flushed after `flush_timeout_ms`);
+    /// the port's next event continues the same line. Never set on TX.
+    pub partial: bool,
+    pub direction: Direction,
+}
+
+#[cfg(test)]
+impl PortEvent {
+    /// A complete line of `data` received on `port` now.
+    pub fn rx(port: &str, data: &[u8]) -> Self {
+        Self {
+            port: port.into(),
+            data: Bytes::copy_from_slice(data),
+            timestamp: Local::now(),
+            partial: false,
+            direction: Direction::Rx,
+        }
+    }
+}
+
+/// Input modem status lines of a port, polled by its reader thread.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ModemLines {
+    /// Clear To Send
+    pub cts: bool,
+    /// Data Set Ready
+    pub dsr: bool,
+    /// Ring Indicator
+    pub ri: bool,
+    /// Carrier Detect
+    pub cd: bool,
+}
+
+impl ModemLines {
+    /// Reads all four lines from the device.
+    fn read(port: &mut dyn SerialPort) -> serialport::Result<Self> {
+        Ok(Self {
+            cts: port.read_clear_to_send()?,
+            dsr: port.read_data_set_ready()?,
+            ri: port.read_ring_indicator()?,
+            cd: port.read_carrier_detect()?,
+        })
+    }
+
+    /// Returns each line's name and level, in display order.
+    pub fn levels(&self) -> [(&'static str, bool); 4] {
+        [
+            ("CTS", self.cts),
+            ("DSR", self.dsr),
+            ("RI", self.ri),
+            ("CD", self.cd),
+        ]
+    }
+
+    /// Describes the lines that differ from `old`, e.g. `CTS on, CD off`.
+    pub fn changes_from(&self, old: ModemLines) -> String {
+        describe_levels(
+            self.levels()
+                .into_iter()
+                .zip(old.levels())
+                .filter(|(new, old)| new != old)
+                .map(|(new, _)| new),
+        )
+    }
+}
+
+impl fmt::Display for ModemLines {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&describe_levels(self.levels().into_iter()))
+    }
+}
+
+/// Joins line levels as `CTS on, DSR off`.
+fn describe_levels(levels: impl Iterator<Item = (&'static str, bool)>) -> String {
+    levels
+        .map(|(name, on)| format!("{name} {}", if on { "on" } else { "off" }))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Extra consumers of received data besides the UI and logger (scripts).
+///
+/// Shared by the hub and every reader thread; senders whose receiver was
+/// dropped are pruned on the next event.
+pub type Subscribers = Arc<Mutex<Vec<Sender<Arc<PortEvent>>>>>;
+
+/// Triggers checked by every reader thread, replaced on config reload.
+pub type SharedTriggers = Arc<RwLock<Vec<ActiveTrigger>>>;
+
+/// A configured trigger with its cooldown state.
+pub struct ActiveTrigger {
+    pub trigger: Trigger,
+    /// When the trigger last fired on each port
+    last_fired: Mutex<HashMap<Arc<str>, Instant>>,
+}
+
+impl From<Trigger> for ActiveTrigger {
+    fn from(trigger: Trigger) -> Self {
+        Self {
+            trigger,
+            last_fired: Mutex::default(),
+        }
+    }
+}
+
+impl ActiveTrigger {
+    /// Returns true if `text` received on `port` fires the trigger.
+    ///
+    /// Firing starts the cooldown on that port only, during which further
+    /// matches there are ignored.
+    pub fn fires(&self, port: &Arc<str>, text: &str) -> bool {
+        if !self.trigger.matches(port, text) {
+            return false;
+        }
+
+        let mut last_fired = self.last_fired.lock().unwrap();
+        if last_fired
+            .get(port)
+            .is_some_and(|last| last.elapsed() < self.trigger.cooldown)
+        {
+            return false;
+        }
+        last_fired.insert(port.clone(), Instant::now());
+        true
+    }
+}
+
+/// Connection state of a port, driven by its reader thread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionState {
+    /// Device is open and being read.
+    Connected,
+    /// Device went away and reconnect is disabled.
+    Disconnected,
+    /// Device went away; waiting for it to come back.
+    Reconnecting {
+        /// Number of reopen attempts made so far
+        attempt: u32,
+    },
+    /// Closed by the user; threads are stopped and the device is released.
+    Closed,
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionState::Connected => f.write_str("connected"),
+            ConnectionState::Disconnected => f.write_str("disconnected"),
+            ConnectionState::Reconnecting { attempt } => write!(f, "reconnecting ({attempt})"),
+            ConnectionState::Closed => f.write_str("closed"),
+        }
+    }
+}
+
+/// State shared between a `Port` handle and its reader/writer threads.
+struct Shared {
+    /// Current connection state
+    state: Mutex<ConnectionState>,
+    /// Handle used by the writer thread, swapped in by the reader on reconnect
+    writer: Mutex<Option<Box<dyn SerialPort>>>,
+    /// Set by `Port::close` to stop the reader thread and cut short the
+    /// writer's waits
+    shutdown: AtomicBool,
+    /// Last DTR level set, for toggling; asserted whenever the device opens
+    dtr: AtomicBool,
+    /// Last RTS level set, for toggling; asserted whenever the device opens
+    rts: AtomicBool,
+}
+
+impl Shared {
+    /// Returns true once `Port::close` has asked the threads to stop.
+    fn is_shutdown(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
 }
 
-/// A connected serial port with running reader/writer threads.
+/// Everything the reader thread needs to read, reconnect and report.
+struct Reader {
+    name: Arc<str>,
+    config: Arc<PortConfig>,
+    shared: Arc<Shared>,
+    ui_tx: Sender<UiEvent>,
+    log_tx: Sender<LoggerEvent>,
+    subscribers: Subscribers,
+    triggers: SharedTriggers,
+    /// Queue of the port's writer thread, for trigger responses
+    writer_tx: mpsc::SyncSender<WriterCommand>,
+    stats: SharedStats,
+    /// Partial fragments of the line being received, for triggers
+    partial_line: RefCell<BytesMut>,
+}
+
+/// Work queued for a port's writer thread.
+#[derive(Debug)]
+pub enum WriterCommand {
+    /// Bytes to write as-is
+    Data(Bytes),
+    /// Control line steps to run in order
+    Control(ControlSequence),
+}
+
+/// Everything the writer thread needs to write, set control lines and
+/// echo what it sent.
+struct Writer {
+    name: Arc<str>,
+    config: Arc<PortConfig>,
+    shared: Arc<Shared>,
+    ui_tx: Sender<UiEvent>,
+    log_tx: Sender<LoggerEvent>,
+    stats: SharedStats,
+}
+
+/// Why the read loop stopped.
+enum ReadExit {
+    /// The device returned an error or EOF
+    Lost(String),
+    /// The port was closed or the UI receiver was dropped
+    Closed,
+}
+
+/// Running reader/writer threads of an open port.
+struct Connection {
+    writer_tx: mpsc::SyncSender<WriterCommand>,
+    shared: Arc<Shared>,
+    reader: JoinHandle<()>,
+    writer: JoinHandle<()>,
+}
+
+/// A configured serial port and, while open, its reader/writer threads.
 pub struct Port {
-    pub writer_tx: mpsc::SyncSender<Bytes>,
     pub config: Arc<PortConfig>,
+    /// Traffic and error counters since the port was first opened, kept
+    /// by the hub across reopens
+    pub stats: SharedStats,
+    /// Running threads, `None` while the port is closed
+    conn: Option<Connection>,
 }
 
 impl Port {
-    fn find_delim(buf: &[u8], le: &LineEnding) -> Option<usize> {
-        match le {
-            LineEnding::Lf => memchr(b'\n', buf),
-            LineEnding::Cr => memchr(b'\r', buf),
-            LineEnding::CrLf => memmem::find(buf, b"\r\n"),
-        }
+    /// Opens the device described by `config` with its configured framing.
+    fn open_device(config: &PortConfig) -> Result<Box<dyn SerialPort>, serialport::Error> {
+        serialport::new(config.path.to_string_lossy(), config.baud_rate)
+            .data_bits(config.data_bits.into())
+            .stop_bits(config.stop_bits.into())
+            .parity(config.parity.into())
+            .flow_control(config.flow_control.into())
+            .timeout(Duration::from_millis(10))
+            .open()
     }
 
-    fn spawn_reader(
-        mut port: Box<dyn SerialPort>,
+    fn spawn_reader(port: Box<dyn SerialPort>, reader: Reader) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            let mut port = port;
+            while let ReadExit::Lost(reason) = reader.read_loop(port.as_mut()) {
+                // Stop the writer from using the dead handle
+                *reader.shared.writer.lock().unwrap() = None;
+                let _ = reader.ui_tx.send(UiEvent::ShowNotification(
+                    format!("{}: {reason}", reader.name).into(),
+                ));
+
+                if !reader.config.reconnect {
+                    reader.set_state(ConnectionState::Disconnected);
+                    break;
+                }
+
+                match reader.reconnect() {
+                    Some(new_port) => port = new_port,
+                    None => break,
+                }
+            }
+        })
+    }
+
+    fn spawn_writer(writer_rx: Receiver<WriterCommand>, writer: Writer) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            while let Ok(command) = writer_rx.recv() {
+                match command {
+                    WriterCommand::Data(data) => writer.write(data),
+                    WriterCommand::Control(sequence) => writer.control(&sequence),
+                }
+            }
+        })
+    }
+
+    /// Opens a port and spawns reader/writer threads that count into
+    /// `stats`.
+    pub fn open(
         name: Arc<str>,
+        config: PortConfig,
+        stats: SharedStats,
         ui_tx: Sender<UiEvent>,
         log_tx: Sender<LoggerEvent>,
-        line_ending: LineEnding,
-    ) {
-        std::thread::spawn(move || {
-            let mut tmp_buf = [0; 4096];
-            let mut accum = BytesMut::new();
-            loop {
-                // Read data
-                let read_data_len = match port.read(&mut tmp_buf) {
-                    Ok(0) => break,
-                    Ok(n) => n,
-                    Err(e)
-                        if e.kind() == std::io::ErrorKind::TimedOut
-                            || e.kind() == std::io::ErrorKind::WouldBlock =>
-                    {
-                        continue;
+        subscribers: Subscribers,
+        triggers: SharedTriggers,
+    ) -> Result<Self, SerialError> {
+        let port = Port::open_device(&config)?;
+        let config = Arc::new(config);
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(ConnectionState::Connected),
+            writer: Mutex::new(Some(port.try_clone()?)),
+            shutdown: AtomicBool::new(false),
+            dtr: AtomicBool::new(true),
+            rts: AtomicBool::new(true),
+        });
+
+        let (writer_tx, writer_rx) = mpsc::sync_channel::<WriterCommand>(32);
+
+        // Spawn reader thread
+        let reader = Port::spawn_reader(
+            port,
+            Reader {
+                name: name.clone(),
+                config: config.clone(),
+                shared: shared.clone(),
+                ui_tx: ui_tx.clone(),
+                log_tx: log_tx.clone(),
+                subscribers,
+                triggers,
+                writer_tx: writer_tx.clone(),
+                stats: stats.clone(),
+                partial_line: RefCell::default(),
+            },
+        );
+
+        let writer = Port::spawn_writer(
+            writer_rx,
+            Writer {
+                name,
+                config: config.clone(),
+                shared: shared.clone(),
+                ui_tx,
+                log_tx,
+                stats: stats.clone(),
+            },
+        );
+
+        Ok(Port {
+            config,
+            stats,
+            conn: Some(Connection {
+                writer_tx,
+                shared,
+                reader,
+                writer,
+            }),
+        })
+    }
+
+    /// Creates a port entry without opening the device.
+    ///
+    /// Used for configured ports that failed to open or were closed,
+    /// so they can still be listed and reopened later.
+    pub fn closed(config: PortConfig, stats: SharedStats) -> Self {
+        Port {
+            config: Arc::new(config),
+            stats,
+            conn: None,
+        }
+    }
+
+    /// Stops the reader/writer threads and releases the device.
+    ///
+    /// Blocks until both threads have exited. Does nothing if already closed.
+    pub fn close(&mut self) {
+        let Some(conn) = self.conn.take() else {
+            return;
+        };
+
+        // Dropping the senders ends the writer loop; the reader holds one
+        // too, so it has to stop first
+        drop(conn.writer_tx);
+        conn.shared.shutdown.store(true, Ordering::Relaxed);
+
+        let _ = conn.reader.join();
+        let _ = conn.writer.join();
+        *conn.shared.writer.lock().unwrap() = None;
+    }
+
+    /// Queues data or control steps for the writer thread.
+    pub fn send(&self, name: &Arc<str>, command: WriterCommand) -> Result<(), SerialError> {
+        let conn = self
+            .conn
+            .as_ref()
+            .ok_or_else(|| SerialError::NotConnected(name.clone()))?;
+        conn.writer_tx.try_send(command).inspect_err(|_| {
+            self.stats.record_dropped();
+        })?;
+        Ok(())
+    }
+
+    /// Returns the current connection state.
+    pub fn state(&self) -> ConnectionState {
+        self.conn
+            .as_ref()
+            .map(|conn| *conn.shared.state.lock().unwrap())
+            .unwrap_or(ConnectionState::Closed)
+    }
+}
+
+impl Writer {
+    /// Writes data to the device. With `local_echo`, a successful write is
+    /// sent to the UI and logger as a TX event; scripts and triggers only
+    /// see received data.
+    fn write(&self, data: Bytes) {
+        let mut writer = self.shared.writer.lock().unwrap();
+        let Some(port) = writer.as_mut() else {
+            self.stats.record_dropped();
+            self.notify(format!("{}: not connected, data dropped", self.name));
+            return;
+        };
+
+        if let Err(e) = port.write_all(&data) {
+            // Reader thread notices the loss and reconnects
+            *writer = None;
+            self.notify(format!("{}: write error: {e}", self.name));
+            return;
+        }
+        drop(writer);
+        self.stats.record_tx(data.len());
+
+        if self.config.local_echo {
+            let event = Arc::new(PortEvent {
+                port: self.name.clone(),
+                data,
+                timestamp: Local::now(),
+                partial: false,
+                direction: Direction::Tx,
+            });
+            let _ = self.log_tx.send(LoggerEvent::SerialData(event.clone()));
+            let _ = self.ui_tx.send(UiEvent::PortData(event));
+        }
+    }
+
+    /// Runs control line steps in order, stopping at the first error.
+    ///
+    /// The steps that ran, with toggles resolved, are logged and shown as
+    /// a marker line. The device is only locked for each step (not while
+    /// a break or wait lasts), so data queued behind the sequence waits
+    /// for it but the reader does not. Closing the port ends the sequence
+    /// at its current wait, clearing a break first.
+    fn control(&self, sequence: &ControlSequence) {
+        let mut done = Vec::new();
+        for &step in &sequence.0 {
+            let result = match step {
+                ControlStep::Dtr(level) => {
+                    let on = level.unwrap_or(!self.shared.dtr.load(Ordering::Relaxed));
+                    self.with_port(|port| port.write_data_terminal_ready(on))
+                        .map(|()| {
+                            self.shared.dtr.store(on, Ordering::Relaxed);
+                            ControlStep::Dtr(Some(on))
+                        })
+                }
+                ControlStep::Rts(level) => {
+                    let on = level.unwrap_or(!self.shared.rts.load(Ordering::Relaxed));
+                    self.with_port(|port| port.write_request_to_send(on))
+                        .map(|()| {
+                            self.shared.rts.store(on, Ordering::Relaxed);
+                            ControlStep::Rts(Some(on))
+                        })
+                }
+                ControlStep::Break(ms) => {
+                    let result = self.with_port(|port| port.set_break()).and_then(|()| {
+                        let slept =
+                            sleep_interruptible(Duration::from_millis(ms), &self.shared.shutdown);
+                        self.with_port(|port| port.clear_break()).map(|()| slept)
+                    });
+                    match result {
+                        Ok(true) => Ok(step),
+                        Ok(false) => break,
+                        Err(e) => Err(e),
                     }
-                    Err(e) => {
-                        let _ = ui_tx.send(UiEvent::ShowNotification(
-                            format!("{name}: read error: {e}").into(),
-                        ));
+                }
+                ControlStep::Wait(ms) => {
+                    if !sleep_interruptible(Duration::from_millis(ms), &self.shared.shutdown) {
                         break;
                     }
-                };
+                    Ok(step)
+                }
+            };
+            match result {
+                Ok(step) => done.push(step),
+                Err(e) => {
+                    self.notify(format!("{}: {step}: {e}", self.name));
+                    break;
+                }
+            }
+        }
+        if done.is_empty() {
+            return;
+        }
 
-                // Add to the accumulator including the line ending
-                accum.extend_from_slice(&tmp_buf[..read_data_len]);
+        let done = ControlSequence(done);
+        let _ = self.log_tx.send(LoggerEvent::Marker {
+            port: self.name.clone(),
+            text: done.to_string(),
+            timestamp: Local::now(),
+        });
+        let _ = self
+            .ui_tx
+            .send(UiEvent::ControlLines(self.name.clone(), done));
+    }
 
-                // Check if accumulator has the line ending
-                while let Some(delim_index) = Port::find_delim(&accum, &line_ending) {
-                    let data = accum.split_to(delim_index + line_ending.len());
+    /// Runs `f` on the device, failing if it is not connected.
+    fn with_port<T>(
+        &self,
+        f: impl FnOnce(&mut dyn SerialPort) -> serialport::Result<T>,
+    ) -> Result<T, String> {
+        let mut writer = self.shared.writer.lock().unwrap();
+        let port = writer.as_mut().ok_or("not connected")?;
+        f(port.as_mut()).map_err(|e| e.to_string())
+    }
 
-                    // only take payload not the line ending
-                    let data = data.freeze();
+    fn notify(&self, msg: String) {
+        let _ = self.ui_tx.send(UiEvent::ShowNotification(msg.into()));
+    }
+}
 
-                    // Send data to UI and logger
-                    let port_event = PortEvent {
-                        port: name.clone(),
-                        data,
-                        timestamp: Local::now(),
-                    };
+impl Reader {
+    /// Reads from the port, emitting one `PortEvent` per line ended by
+    /// `rx_delimiter`,
+    /// until the device is lost or the UI goes away.
+    ///
+    /// Bytes still waiting for a line ending are emitted as a partial event
+    /// once the port has been quiet for `flush_timeout_ms`, or when the
+    /// device is lost. A quiet flush holds back the start of a multi-byte
+    /// delimiter (the `\r` of `crlf`), so it is not split from its rest.
+    ///
+    /// A regex delimiter ending at the last byte received only splits the
+    /// line once a read times out (or the device is lost), whatever
+    /// `flush_timeout_ms`, so end-anchored patterns like `/>\s*$/` see
+    /// whole lines.
+    fn read_loop(&self, port: &mut dyn SerialPort) -> ReadExit {
+        let delimiter = &self.config.rx_delimiter;
+        let flush_timeout = (self.config.flush_timeout_ms > 0)
+            .then(|| Duration::from_millis(self.config.flush_timeout_ms));
+        let mut tmp_buf = [0; 4096];
+        let mut accum = BytesMut::new();
+        let mut last_read = Instant::now();
+        // Polled until the device turns out not to support it (e.g. a pty)
+        let mut modem_polling = true;
+        let mut modem = None;
+        let mut last_modem_poll = Instant::now();
+        // A line cut off by a lost device does not continue here
+        self.partial_line.borrow_mut().clear();
+        loop {
+            if self.shared.is_shutdown() {
+                return ReadExit::Closed;
+            }
 
-                    let event = Arc::new(port_event);
-                    let _ = log_tx.send(LoggerEvent::SerialData(event.clone()));
+            if modem_polling && last_modem_poll.elapsed() >= MODEM_POLL_INTERVAL {
+                last_modem_poll = Instant::now();
+                modem_polling = self.poll_modem(port, &mut modem);
+            }
 
-                    if ui_tx.send(UiEvent::PortData(event)).is_err() {
-                        break; // receiver dropped
+            // Read data
+            let read_data_len = match port.read(&mut tmp_buf) {
+                Ok(0) => {
+                    self.emit_lines(&mut accum, true);
+                    self.emit(accum.split().freeze(), true);
+                    return ReadExit::Lost("device closed".into());
+                }
+                Ok(n) => n,
+                Err(e)
+                    if e.kind() == std::io::ErrorKind::TimedOut
+                        || e.kind() == std::io::ErrorKind::WouldBlock =>
+                {
+                    // Quiet line: lines ended by a regex match at the very
+                    // end are complete now
+                    if !self.emit_lines(&mut accum, true) {
+                        return ReadExit::Closed;
+                    }
+                    // Show what we have so far
+                    if flush_timeout.is_some_and(|timeout| last_read.elapsed() >= timeout) {
+                        let held = delimiter.partial_suffix(&accum);
+                        let data = accum.split_to(accum.len() - held).freeze();
+                        if !self.emit(data, true) {
+                            return ReadExit::Closed;
+                        }
                     }
+                    continue;
                 }
+                Err(e) => {
+                    self.stats.record_read_error();
+                    self.emit_lines(&mut accum, true);
+                    self.emit(accum.split().freeze(), true);
+                    return ReadExit::Lost(format!("read error: {e}"));
+                }
+            };
+            last_read = Instant::now();
+
+            // Add to the accumulator
+            accum.extend_from_slice(&tmp_buf[..read_data_len]);
+
+            if !self.emit_lines(&mut accum, false) {
+                return ReadExit::Closed; // receiver dropped
             }
+        }
+    }
+
+    /// Emits every complete line in `accum`, delimiter included, leaving
+    /// the rest. `settled` is passed on to `RxDelimiter::find`.
+    ///
+    /// Returns false if the UI receiver is gone.
+    fn emit_lines(&self, accum: &mut BytesMut, settled: bool) -> bool {
+        while let Some(delim) = self.config.rx_delimiter.find(accum, settled) {
+            let data = accum.split_to(delim.end).freeze();
+            if !self.emit(data, false) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Reads the modem status lines and reports them if they differ from
+    /// `last`.
+    ///
+    /// The first reading of a connection only goes to the UI; later changes
+    /// are logged as a marker too. Returns false if the device cannot report
+    /// its lines.
+    fn poll_modem(&self, port: &mut dyn SerialPort, last: &mut Option<ModemLines>) -> bool {
+        let Ok(lines) = ModemLines::read(port) else {
+            return false;
+        };
+        if *last == Some(lines) {
+            return true;
+        }
+
+        if let Some(old) = last.replace(lines) {
+            let _ = self.log_tx.send(LoggerEvent::Marker {
+                port: self.name.clone(),
+                text: lines.changes_from(old),
+                timestamp: Local::now(),
+            });
+        }
+        let _ = self
+            .ui_tx
+            .send(UiEvent::ModemChanged(self.name.clone(), lines));
+        true
+    }
+
+    /// Sends received data to the UI, logger and subscribers. Empty data
+    /// is skipped.
+    ///
+    /// Returns false if the UI receiver is gone.
+    fn emit(&self, data: Bytes, partial: bool) -> bool {
+        if data.is_empty() {
+            return true;
+        }
+
+        self.stats.record_rx(data.len(), partial);
+        let event = Arc::new(PortEvent {
+            port: self.name.clone(),
+            data,
+            timestamp: Local::now(),
+            partial,
+            direction: Direction::Rx,
         });
+        let _ = self.log_tx.send(LoggerEvent::SerialData(event.clone()));
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|tx| tx.send(event.clone()).is_ok());
+        let alive = self.ui_tx.send(UiEvent::PortData(event.clone())).is_ok();
+        self.run_triggers(&event);
+        alive
     }
 
-    fn spawn_writer(
-        mut port: Box<dyn SerialPort>,
-        name: Arc<str>,
-        writer_rx: Receiver<Bytes>,
-        ui_tx: Sender<UiEvent>,
-    ) {
-        std::thread::spawn(move || {
-            while let Ok(data) = writer_rx.recv() {
-                if let Err(e) = port.write_all(&data) {
-                    let _ = ui_tx.send(UiEvent::ShowNotification(
-                        format!("{name}: write error: {e}").into(),
-                    ));
-                    break;
+    /// Runs the actions of every trigger that the line completed by
+    /// `event` fires.
+    ///
+    /// Partial fragments are held until the rest of their line arrives,
+    /// and triggers see the whole line without its trailing CR/LF, so a
+    /// pattern fires at most once per line and `^...$` anchors the line.
+    /// Responses are queued on this port's writer; everything else is
+    /// left to the UI, and markers are logged here.
+    fn run_triggers(&self, event: &PortEvent) {
+        let mut partial_line = self.partial_line.borrow_mut();
+        let triggers = self.triggers.read().unwrap();
+        if triggers.is_empty() {
+            partial_line.clear();
+            return;
+        }
+        if event.partial {
+            partial_line.extend_from_slice(&event.data);
+            return;
+        }
+
+        let line = if partial_line.is_empty() {
+            event.data.clone()
+        } else {
+            partial_line.extend_from_slice(&event.data);
+            partial_line.split().freeze()
+        };
+        let text = String::from_utf8_lossy(&line);
+        let text = text.trim_end_matches(['\r', '\n']);
+        for trigger in triggers.iter().filter(|t| t.fires(&self.name, text)) {
+            for action in &trigger.trigger.actions {
+                match action {
+                    TriggerAction::Send(data) => {
+                        let command = WriterCommand::Data(data.clone());
+                        if self.writer_tx.try_send(command).is_err() {
+                            self.stats.record_dropped();
+                            let _ = self.ui_tx.send(UiEvent::ShowNotification(
+                                format!("{}: trigger response dropped", self.name).into(),
+                            ));
+                        }
+                        continue;
+                    }
+                    TriggerAction::Marker(text) => {
+                        let _ = self.log_tx.send(LoggerEvent::Marker {
+                            port: self.name.clone(),
+                            text: text.clone(),
+                            timestamp: Local::now(),
+                        });
+                    }
+                    _ => {}
                 }
+                let _ = self
+                    .ui_tx
+                    .send(UiEvent::TriggerFired(self.name.clone(), action.clone()));
+            }
+        }
+    }
+
+    /// Retries opening the configured path with exponential backoff.
+    ///
+    /// Returns the new reader handle once the device is back, or `None`
+    /// if the UI went away while waiting.
+    fn reconnect(&self) -> Option<Box<dyn SerialPort>> {
+        let max_delay = Duration::from_millis(self.config.reconnect_max_interval_ms);
+        let mut delay = Duration::from_millis(self.config.reconnect_interval_ms).min(max_delay);
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            if !self.set_state(ConnectionState::Reconnecting { attempt })
+                || !sleep_interruptible(delay, &self.shared.shutdown)
+            {
+                return None;
+            }
+
+            let Ok(port) = Port::open_device(&self.config) else {
+                delay = (delay * 2).min(max_delay);
+                continue;
+            };
+            let Ok(writer) = port.try_clone() else {
+                delay = (delay * 2).min(max_delay);
+                continue;
+            };
+
+            // A reopened device starts with both lines asserted again
+            self.shared.dtr.store(true, Ordering::Relaxed);
+            self.shared.rts.store(true, Ordering::Relaxed);
+            *self.shared.writer.lock().unwrap() = Some(writer);
+            self.stats.record_reconnect();
+            if !self.set_state(ConnectionState::Connected) {
+                return None;
+            }
+            return Some(port);
+        }
+    }
+
+    /// Updates the shared state and tells the UI about the transition.
+    ///
+    /// Returns false if the UI receiver is gone.
+    fn set_state(&self, state: ConnectionState) -> bool {
+        *self.shared.state.lock().unwrap() = state;
+        self.ui_tx
+            .send(UiEvent::ConnectionChanged(self.name.clone(), state))
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serialport::TTYPort;
+
+    use super::*;
+
+    /// Opens `config` with `triggers` on the far end of a new pty,
+    /// returning the device end to write to and the port's UI events.
+    fn open_pty(
+        mut config: PortConfig,
+        triggers: SharedTriggers,
+    ) -> (TTYPort, Port, Receiver<UiEvent>) {
+        let (device, far_end) = TTYPort::pair().unwrap();
+        config.path = far_end.name().unwrap().into();
+        let (ui_tx, ui_rx) = mpsc::channel();
+        let (log_tx, _) = mpsc::channel();
+        let port = Port::open(
+            "dut".into(),
+            config,
+            SharedStats::default(),
+            ui_tx,
+            log_tx,
+            Subscribers::default(),
+            triggers,
+        )
+        .unwrap();
+        (device, port, ui_rx)
+    }
+
+    /// Collects `count` received data events as `(text, partial)`.
+    fn received(ui_rx: &Receiver<UiEvent>, count: usize) -> Vec<(String, bool)> {
+        let mut lines = Vec::new();
+        while lines.len() < count {
+            match ui_rx.recv_timeout(Duration::from_secs(2)).unwrap() {
+                UiEvent::PortData(event) => lines.push((
+                    String::from_utf8_lossy(&event.data).into_owned(),
+                    event.partial,
+                )),
+                _ => continue,
             }
+        }
+        lines
+    }
+
+    #[test]
+    fn test_modem_changes() {
+        let old = ModemLines {
+            cts: true,
+            cd: true,
+            ..Default::default()
+        };
+        let new = ModemLines {
+            cts: true,
+            dsr: true,
+            ..Default::default()
+        };
+        assert_eq!(new.changes_from(old), "DSR on, CD off");
+        assert_eq!(new.changes_from(new), "");
+        assert_eq!(new.to_string(), "CTS on, DSR on, RI off, CD off");
+    }
+
+    #[test]
+    fn test_trigger_cooldown() {
+        let trigger = ActiveTrigger::from(Trigger {
+            ports: None,
+            pattern: regex::Regex::new("autoboot").unwrap(),
+            actions: vec![TriggerAction::Send(Bytes::from_static(b" "))],
+            cooldown: Duration::from_secs(60),
         });
+        let (com1, com2): (Arc<str>, Arc<str>) = ("com1".into(), "com2".into());
+
+        assert!(!trigger.fires(&com1, "Booting"));
+        assert!(trigger.fires(&com1, "stop autoboot"));
+        assert!(!trigger.fires(&com1, "stop autoboot"));
+        // Each port has its own cooldown
+        assert!(trigger.fires(&com2, "stop autoboot"));
     }
-    /// Opens a port and spawns rea der/writer threads.
-    pub fn open(
-        name: Arc<str>,
-        config: PortConfig,
-        ui_tx: Sender<UiEvent>,
-        log_tx: Sender<LoggerEvent>,
-    ) -> Result<Self, SerialError> {
-        let port = serialport::new(config.path.to_string_lossy(), config.baud_rate)
-            .timeout(Duration::from_millis(10))
-            .open()?;
 
-        // Spawn reader thread
-        Port::spawn_reader(
-            port.try_clone()?,
-            name.clone(),
-            ui_tx.clone(),
-            log_tx.clone(),
-            config.line_ending,
+    #[test]
+    fn test_regex_delimiter_without_flush() {
+        let (mut device, mut port, ui_rx) = open_pty(
+            PortConfig {
+                rx_delimiter: r"/\n|> $/".parse().unwrap(),
+                flush_timeout_ms: 0,
+                ..Default::default()
+            },
+            SharedTriggers::default(),
         );
 
-        let (writer_tx, writer_rx) = mpsc::sync_channel::<Bytes>(32);
-        Port::spawn_writer(port, name, writer_rx, ui_tx);
+        // The prompt ends the data, so it is only a line once the port
+        // goes quiet
+        device.write_all(b"boot\nlogin> ").unwrap();
+        assert_eq!(
+            received(&ui_rx, 2),
+            [("boot\n".into(), false), ("login> ".into(), false)]
+        );
+        port.close();
+    }
 
-        Ok(Port {
-            writer_tx,
-            config: Arc::new(config),
-        })
+    #[test]
+    fn test_triggers_see_whole_lines() {
+        let marker = |pattern: &str| {
+            ActiveTrigger::from(Trigger {
+                ports: None,
+                pattern: regex::Regex::new(pattern).unwrap(),
+                actions: vec![TriggerAction::Marker(pattern.into())],
+                cooldown: Duration::ZERO,
+            })
+        };
+        let triggers = Arc::new(RwLock::new(vec![marker("^stop autoboot$"), marker("stop")]));
+        let (mut device, mut port, ui_rx) = open_pty(
+            PortConfig {
+                flush_timeout_ms: 20,
+                ..Default::default()
+            },
+            triggers,
+        );
+
+        // The start of the line is shown before the rest arrives
+        device.write_all(b"stop").unwrap();
+        assert_eq!(received(&ui_rx, 1), [("stop".into(), true)]);
+        device.write_all(b" autoboot\r\n").unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        port.close();
+
+        let fired: Vec<_> = ui_rx
+            .try_iter()
+            .filter_map(|event| match event {
+                UiEvent::TriggerFired(_, TriggerAction::Marker(text)) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fired, ["^stop autoboot$", "stop"]);
     }
 }
@@ -0,0 +1,245 @@
+//! Per-port throughput and error counters.
+//!
+//! Updated by a port's reader and writer threads (and by queueing sends),
+//! read by the UI for the stats popup and `/stats`. Rates are counted in
+//! whole seconds; the last `HISTORY` of them are kept for sparklines.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Number of per-second rates kept.
+pub const HISTORY: usize = 60;
+
+/// Bytes and lines in one second.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rate {
+    pub bytes: u64,
+    pub lines: u64,
+}
+
+/// Traffic in one direction.
+#[derive(Debug, Clone, Default)]
+pub struct Traffic {
+    pub bytes: u64,
+    /// Lines received, or writes sent
+    pub lines: u64,
+    /// Rates of the last `HISTORY` whole seconds, oldest first
+    pub history: VecDeque<Rate>,
+    /// Highest bytes/s and lines/s seen (each on its own)
+    pub peak: Rate,
+    /// Counts of the second in progress
+    current: Rate,
+}
+
+impl Traffic {
+    fn add(&mut self, bytes: usize, lines: u64) {
+        self.bytes += bytes as u64;
+        self.lines += lines;
+        self.current.bytes += bytes as u64;
+        self.current.lines += lines;
+    }
+
+    /// Returns the rate of the last whole second.
+    pub fn rate(&self) -> Rate {
+        self.history.back().copied().unwrap_or_default()
+    }
+
+    /// Ends the second in progress.
+    fn push_second(&mut self) {
+        let done = std::mem::take(&mut self.current);
+        self.peak.bytes = self.peak.bytes.max(done.bytes);
+        self.peak.lines = self.peak.lines.max(done.lines);
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(done);
+    }
+}
+
+/// Counters of one port since it was first opened.
+#[derive(Debug, Clone, Default)]
+pub struct PortStats {
+    pub rx: Traffic,
+    pub tx: Traffic,
+    /// Device read errors, each followed by a reconnect or disconnect
+    pub read_errors: u64,
+    /// Successful automatic reconnects and manual reopens
+    pub reconnects: u64,
+    /// Sends dropped because the queue was full or the port not connected
+    pub dropped: u64,
+    /// Start of the second in progress
+    second_start: Option<Instant>,
+}
+
+impl PortStats {
+    /// Ends every second that has passed by `now`.
+    fn roll(&mut self, now: Instant) {
+        let start = *self.second_start.get_or_insert(now);
+        let elapsed = now.duration_since(start).as_secs();
+        if elapsed == 0 {
+            return;
+        }
+        // Older seconds would fall out of the history anyway
+        for _ in 0..elapsed.min(HISTORY as u64) {
+            self.rx.push_second();
+            self.tx.push_second();
+        }
+        self.second_start = Some(start + Duration::from_secs(elapsed));
+    }
+}
+
+/// One-line summary, as written to the log by `/stats`.
+impl fmt::Display for PortStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, traffic) in [("rx", &self.rx), ("tx", &self.tx)] {
+            let rate = traffic.rate();
+            write!(
+                f,
+                "{name} {} {} lines ({}/s {} lines/s, peak {}/s {} lines/s), ",
+                format_bytes(traffic.bytes),
+                traffic.lines,
+                format_bytes(rate.bytes),
+                rate.lines,
+                format_bytes(traffic.peak.bytes),
+                traffic.peak.lines,
+            )?;
+        }
+        write!(
+            f,
+            "read errors {}, reconnects {}, dropped {}",
+            self.read_errors, self.reconnects, self.dropped
+        )
+    }
+}
+
+/// Stats shared by a port's threads and its `Port` handle.
+#[derive(Clone, Default)]
+pub struct SharedStats(Arc<Mutex<PortStats>>);
+
+impl SharedStats {
+    /// Counts received data; a partial line is counted once it is complete.
+    pub fn record_rx(&self, bytes: usize, partial: bool) {
+        self.update(|stats| stats.rx.add(bytes, u64::from(!partial)));
+    }
+
+    /// Counts one successful write.
+    pub fn record_tx(&self, bytes: usize) {
+        self.update(|stats| stats.tx.add(bytes, 1));
+    }
+
+    pub fn record_read_error(&self) {
+        self.update(|stats| stats.read_errors += 1);
+    }
+
+    pub fn record_reconnect(&self) {
+        self.update(|stats| stats.reconnects += 1);
+    }
+
+    pub fn record_dropped(&self) {
+        self.update(|stats| stats.dropped += 1);
+    }
+
+    /// Returns a copy of the stats, with the seconds up to now ended.
+    pub fn snapshot(&self) -> PortStats {
+        let mut stats = self.0.lock().unwrap();
+        stats.roll(Instant::now());
+        stats.clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut PortStats)) {
+        let mut stats = self.0.lock().unwrap();
+        stats.roll(Instant::now());
+        f(&mut stats);
+    }
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 KiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rates() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut stats = PortStats::default();
+
+        stats.roll(at(0));
+        stats.rx.add(100, 2);
+        stats.roll(at(500));
+        stats.rx.add(50, 1);
+        stats.roll(at(1200));
+        stats.rx.add(10, 1);
+        assert_eq!(
+            stats.rx.rate(),
+            Rate {
+                bytes: 150,
+                lines: 3
+            }
+        );
+
+        // Quiet seconds count as zero, the peak stays
+        stats.roll(at(3100));
+        assert_eq!(stats.rx.history.len(), 3);
+        assert_eq!(stats.rx.rate(), Rate::default());
+        assert_eq!(
+            stats.rx.history[1],
+            Rate {
+                bytes: 10,
+                lines: 1
+            }
+        );
+        assert_eq!(
+            stats.rx.peak,
+            Rate {
+                bytes: 150,
+                lines: 3
+            }
+        );
+        assert_eq!((stats.rx.bytes, stats.rx.lines), (160, 4));
+
+        stats.roll(at(500_000));
+        assert_eq!(stats.rx.history.len(), HISTORY);
+
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 20), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_log_line() {
+        let start = Instant::now();
+        let mut stats = PortStats::default();
+        stats.roll(start);
+        stats.rx.add(2048, 20);
+        stats.tx.add(12, 3);
+        stats.reconnects = 1;
+        stats.dropped = 2;
+        stats.roll(start + Duration::from_millis(1500));
+
+        assert_eq!(
+            stats.to_string(),
+            "rx 2.0 KiB 20 lines (2.0 KiB/s 20 lines/s, peak 2.0 KiB/s 20 lines/s), \
+             tx 12 B 3 lines (12 B/s 3 lines/s, peak 12 B/s 3 lines/s), \
+             read errors 0, reconnects 1, dropped 2"
+        );
+    }
+}
@@ -22,7 +22,9 @@ impl FromStr for Color {
         // Hex color
         if s.starts_with('#') {
             if s.len() != 7 {
-                return Err(ConfigError::InvalidColor("hex color must be #RRGGBB".into()));
+                return Err(ConfigError::InvalidColor(
+                    "hex color must be #RRGGBB".into(),
+                ));
             }
             let r = u8::from_str_radix(&s[1..3], 16)?;
             let g = u8::from_str_radix(&s[3..5], 16)?;
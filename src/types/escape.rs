@@ -0,0 +1,86 @@
+//! Raw bytes typed as text: backslash escapes and hex strings.
+
+use crate::error::BytesError;
+
+/// Decodes `\r`, `\n`, `\t`, `\0`, `\\` and `\xHH` escapes into bytes.
+///
+/// Everything else is taken as UTF-8 text.
+pub fn unescape(s: &str) -> Result<Vec<u8>, BytesError> {
+    let mut out = Vec::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('r') => out.push(b'\r'),
+            Some('n') => out.push(b'\n'),
+            Some('t') => out.push(b'\t'),
+            Some('0') => out.push(0),
+            Some('\\') => out.push(b'\\'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = (hex.len() == 2 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .then(|| u8::from_str_radix(&hex, 16).ok())
+                    .flatten()
+                    .ok_or_else(|| BytesError::InvalidEscape(format!("\\x{hex}")))?;
+                out.push(byte);
+            }
+            Some(other) => return Err(BytesError::InvalidEscape(format!("\\{other}"))),
+            None => return Err(BytesError::InvalidEscape("\\".into())),
+        }
+    }
+    Ok(out)
+}
+
+/// Parses hex bytes such as `7F 00 A5`, `7f00a5` or `0x7F 0x00`.
+///
+/// Whitespace separates groups; each group is an optional `0x` prefix
+/// followed by an even number of hex digits.
+pub fn parse_hex(s: &str) -> Result<Vec<u8>, BytesError> {
+    let mut out = Vec::new();
+    for group in s.split_whitespace() {
+        let digits = group
+            .strip_prefix("0x")
+            .or_else(|| group.strip_prefix("0X"))
+            .unwrap_or(group);
+        if digits.is_empty()
+            || digits.len() % 2 != 0
+            || !digits.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            return Err(BytesError::InvalidHex(group.into()));
+        }
+        for i in (0..digits.len()).step_by(2) {
+            out.push(u8::from_str_radix(&digits[i..i + 2], 16).expect("checked hex digits"));
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(r"AT\r").unwrap(), b"AT\r");
+        assert_eq!(unescape(r"\x03\0\\n").unwrap(), b"\x03\0\\n");
+        assert_eq!(unescape("µ").unwrap(), "µ".as_bytes());
+        assert!(unescape(r"\q").is_err());
+        assert!(unescape(r"\x7").is_err());
+        assert!(unescape(r"\x+7").is_err());
+        assert!(unescape("end\\").is_err());
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("7F 00 a5").unwrap(), [0x7f, 0x00, 0xa5]);
+        assert_eq!(parse_hex("0x0D0A 03").unwrap(), [0x0d, 0x0a, 0x03]);
+        assert_eq!(parse_hex("  ").unwrap(), []);
+        assert!(parse_hex("7").is_err());
+        assert!(parse_hex("0x").is_err());
+        assert!(parse_hex("GG").is_err());
+    }
+}
@@ -1,3 +1,5 @@
-//! Shared types used across the application.
+//! Shared types and helpers used across the application.
 
 pub mod color;
+pub mod escape;
+pub mod sleep;
@@ -0,0 +1,26 @@
+//! Waits that a stop flag can cut short.
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Longest single sleep before re-checking a stop flag.
+pub const SLICE: Duration = Duration::from_millis(50);
+
+/// Sleeps for `duration` in `SLICE`s, returning false if `stop` was set
+/// meanwhile.
+pub fn sleep_interruptible(duration: Duration, stop: &AtomicBool) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        if stop.load(Ordering::Relaxed) {
+            return false;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        thread::sleep(remaining.min(SLICE));
+    }
+}
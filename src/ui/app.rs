@@ -4,10 +4,19 @@
 //! keyboard input to the appropriate component based on focus and
 //! popup visibility.
 
-use std::io;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
 use anyhow::Result;
 use bytes::Bytes;
+use chrono::Local;
 use crossterm::{
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
@@ -17,22 +26,37 @@ use crossterm::{
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    text::{Line, Span},
+    style::Color,
 };
 use std::sync::mpsc;
 
 use crate::{
+    config::{
+        self, layout,
+        macros::{self, Macro},
+        port::{ControlSequence, ControlStep},
+        triggers::TriggerAction,
+    },
+    error::ConfigError,
     logger::LoggerEvent,
-    serial::{PortEvent, hub::SerialHub},
+    macro_runner,
+    script::{
+        Script,
+        runner::{self, Outcome},
+    },
+    serial::{
+        ConnectionState, ModemLines, PortEvent, SerialError,
+        hub::{ConfigChange, SerialHub},
+    },
     ui::{
-        HelpPopup, PortListPopup, SendGroupPopup, UiEvent,
-        popup::Notification,
-        widgets::{ConfigAction, DisplayAction, InputBarAction},
+        AddPortPopup, EditPortPopup, HelpPopup, MacroPopup, PortListPopup, SendGroupPopup,
+        StatsPopup, UiEvent,
+        popup::{AddPortAction, EditPortAction, MacroAction, Notification, PortListAction},
+        widgets::{ConfigAction, DisplayAction, Entry, EntryKind, InputBarAction},
     },
 };
 
-use super::widgets::{ConfigBar, Display, InputBar};
+use super::widgets::{ConfigBar, InputBar, Panes};
 
 /// Which widget currently has keyboard focus.
 #[derive(PartialEq, Clone, Copy)]
@@ -42,6 +66,16 @@ pub enum Focus {
     InputBar,
 }
 
+/// A script started with `/run`.
+struct RunningScript {
+    /// File name, used as the tag of its display lines
+    name: Arc<str>,
+    /// Set by `/stop`
+    stop: Arc<AtomicBool>,
+    /// Source line currently running
+    line: usize,
+}
+
 /// Main UI state container.
 ///
 /// Holds references to the serial manager and all UI components.
@@ -50,32 +84,50 @@ pub enum Focus {
 pub struct Ui {
     /// Reference to the serial manager for port operations
     hub: SerialHub,
+    /// Sender handed to background tasks started by the UI (macros)
+    ui_tx: mpsc::Sender<UiEvent>,
     /// Receiver for UI events from background components
     ui_rx: mpsc::Receiver<UiEvent>,
     /// Sender for logger events
     log_tx: mpsc::Sender<LoggerEvent>,
+    /// Path of the ports config, used when saving port sections
+    config_path: PathBuf,
 
     /// Top bar showing port controls
     config_bar: ConfigBar,
-    /// Main area for serial output display
-    display: Display,
+    /// Main area for serial output, split into panes
+    panes: Panes,
     /// Bottom bar for text input
     input_bar: InputBar,
 
     /// Modal popup for port list selection
     port_list_popup: PortListPopup,
+    /// Modal popup for adding a port at runtime
+    add_port_popup: AddPortPopup,
+    /// Modal popup for editing a port's settings
+    edit_port_popup: EditPortPopup,
     /// Modal popup for selecting send targets
     send_group_popup: SendGroupPopup,
+    /// Modal popup for running macros
+    macro_popup: MacroPopup,
     /// Toast notification overlay
     notification_popup: Notification,
     /// Modal popup showing keyboard shortcuts
     help_popup: HelpPopup,
+    /// Modal popup showing per-port throughput and errors
+    stats_popup: StatsPopup,
 
     /// Currently focused widget
     focus: Focus,
 
-    /// Cached display height for key handling
-    display_height: usize,
+    /// Macros from `macros.toml`, by name
+    macros: BTreeMap<String, Macro>,
+    /// Stop flags of the macros currently playing
+    running_macros: HashMap<Arc<str>, Arc<AtomicBool>>,
+    /// Script started with `/run`, at most one at a time
+    script: Option<RunningScript>,
+    /// Modem status lines of the connected ports whose device reports them
+    modem_lines: HashMap<Arc<str>, ModemLines>,
 
     /// Set to true to exit the application
     exit: bool,
@@ -89,27 +141,46 @@ impl Ui {
     /// selected for sending by default.
     pub fn new(
         hub: SerialHub,
+        ui_tx: mpsc::Sender<UiEvent>,
         ui_rx: mpsc::Receiver<UiEvent>,
         log_tx: mpsc::Sender<LoggerEvent>,
+        config_path: PathBuf,
     ) -> Self {
+        let ports: Vec<Arc<str>> = hub.list_ports().into_iter().map(|(name, _)| name).collect();
         let mut send_group_popup = SendGroupPopup::new();
         send_group_popup.select_all(&hub.list_ports());
+        let mut notification_popup = Notification::new();
+        let layout = layout::load_layout(&config_path).unwrap_or_else(|e| {
+            notification_popup.show(format!("{e:#}"));
+            layout::Layout::default()
+        });
 
-        Self {
+        let mut ui = Self {
             hub,
+            ui_tx,
             ui_rx,
             log_tx,
+            config_path,
             config_bar: ConfigBar,
-            display: Display::new(),
-            input_bar: InputBar::new(),
+            panes: Panes::new(layout, &ports),
+            input_bar: InputBar::new(config::history_path()),
             port_list_popup: PortListPopup::new(),
+            add_port_popup: AddPortPopup::new(),
+            edit_port_popup: EditPortPopup::new(),
             send_group_popup,
-            notification_popup: Notification::new(),
+            macro_popup: MacroPopup::new(),
+            notification_popup,
             help_popup: HelpPopup::new(),
+            stats_popup: StatsPopup::new(),
             focus: Focus::InputBar,
-            display_height: 0,
+            macros: BTreeMap::new(),
+            running_macros: HashMap::new(),
+            script: None,
+            modem_lines: HashMap::new(),
             exit: false,
-        }
+        };
+        ui.load_macros();
+        ui
     }
 
     /// Starts the main render loop.
@@ -133,25 +204,30 @@ impl Ui {
 
     /// Renders all UI components to the frame.
     ///
-    /// Layout: ConfigBar (top, 3 lines) | Display (middle, flex) | InputBar (bottom, 3 lines)
+    /// Layout: ConfigBar (top, 3 lines) | Panes (middle, flex) | InputBar (bottom, 3 lines)
     /// Popups are rendered on top if visible.
     pub fn draw(&mut self, frame: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3), // config bar
-                Constraint::Min(5),    // display
+                Constraint::Min(5),    // panes
                 Constraint::Length(3), // input bar
             ])
             .split(frame.area());
 
-        // Cache display height for key handling
-        self.display_height = chunks[1].height.saturating_sub(2) as usize; // minus borders
-
         // Render main widgets with focus indication
-        self.config_bar
-            .render(frame, chunks[0], self.focus == Focus::ConfigBar);
-        self.display
+        let script_status = self
+            .script
+            .as_ref()
+            .map(|script| format!("{}:{}", script.name, script.line));
+        self.config_bar.render(
+            frame,
+            chunks[0],
+            self.focus == Focus::ConfigBar,
+            script_status.as_deref(),
+        );
+        self.panes
             .render(frame, chunks[1], self.focus == Focus::Display);
         self.input_bar
             .render(frame, chunks[2], self.focus == Focus::InputBar);
@@ -160,13 +236,38 @@ impl Ui {
         let ports = self.hub.list_ports();
 
         if self.port_list_popup.visible {
-            self.port_list_popup.render(frame, &ports);
+            let states: Vec<_> = ports
+                .iter()
+                .map(|(name, _)| self.hub.port_state(name).unwrap_or(ConnectionState::Closed))
+                .collect();
+            let modem: Vec<_> = ports
+                .iter()
+                .map(|(name, _)| self.modem_lines.get(name).copied())
+                .collect();
+            self.port_list_popup.render(frame, &ports, &states, &modem);
+        }
+
+        if self.edit_port_popup.is_visible() {
+            self.edit_port_popup.render(frame);
+        }
+
+        if self.add_port_popup.visible {
+            self.add_port_popup.render(frame);
         }
 
         if self.send_group_popup.visible {
             self.send_group_popup.render(frame, &ports);
         }
 
+        if self.macro_popup.visible {
+            self.macro_popup
+                .render(frame, &self.macros, &self.running_macros);
+        }
+
+        if self.stats_popup.visible {
+            self.stats_popup.render(frame, &self.hub.list_stats());
+        }
+
         if self.help_popup.visible {
             self.help_popup.render(frame);
         }
@@ -183,44 +284,158 @@ impl Ui {
     pub fn handle_events(&mut self) -> Result<()> {
         while let Ok(event) = self.ui_rx.try_recv() {
             match event {
-                UiEvent::PortData(port_event) => {
-                    let PortEvent {
-                        port,
-                        data,
-                        timestamp,
-                    } = port_event.as_ref();
-                    let timestamp = timestamp.format("%H:%M:%S%.3f");
-                    let text = String::from_utf8_lossy(data);
-
-                    // Look up port color from config
-                    let port_color = self
-                        .hub
-                        .get_config(port)
-                        .map(|info| info.color.0)
-                        .unwrap_or(Color::Reset);
-
-                    // Build styled line with colored port name
-                    let line = Line::from(vec![
-                        Span::raw(format!("[{timestamp}] ")),
-                        Span::styled(format!("[{port}]"), Style::default().fg(port_color)),
-                        Span::raw(format!(" {text}")),
-                    ]);
-                    self.display.push_line(line);
-                }
+                UiEvent::PortData(port_event) => self.push_port_data(&port_event),
                 UiEvent::ShowNotification(msg) => {
                     self.notification_popup.show(msg.to_string());
                 }
+                UiEvent::ConnectionChanged(port, state) => {
+                    if state != ConnectionState::Connected {
+                        self.modem_lines.remove(&port);
+                    }
+                    self.push_connection_marker(&port, state);
+                }
+                UiEvent::ModemChanged(port, lines) => {
+                    self.modem_lines.insert(port, lines);
+                }
+                UiEvent::ConfigChanged => self.reload_config(),
+                UiEvent::Send {
+                    ports,
+                    data,
+                    line_ending,
+                } => self.send_to(&ports, data, line_ending),
+                UiEvent::MacroFinished(name) => {
+                    self.running_macros.remove(&name);
+                }
+                UiEvent::ScriptProgress(line) => {
+                    if let Some(script) = &mut self.script {
+                        script.line = line;
+                    }
+                }
+                UiEvent::ScriptLog(message) => {
+                    self.push_script_marker(message.to_string(), Color::Cyan);
+                }
+                UiEvent::ScriptFinished(outcome) => self.finish_script(outcome),
+                UiEvent::TriggerFired(port, action) => self.handle_trigger(port, action),
+                UiEvent::ControlLines(port, sequence) => {
+                    self.panes.push_marker(Entry {
+                        timestamp: Local::now(),
+                        port_color: self.port_color(&port),
+                        port,
+                        kind: EntryKind::Marker {
+                            text: sequence.to_string(),
+                            color: Color::Blue,
+                        },
+                    });
+                }
             }
         }
 
-        if event::poll(std::time::Duration::from_millis(16))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    self.handle_key(key);
+        if event::poll(std::time::Duration::from_millis(16))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            self.handle_key(key);
+        }
+        Ok(())
+    }
+
+    /// Applies changes in the config file to the open ports and reloads
+    /// the macros.
+    ///
+    /// Parse errors are reported and leave the current ports untouched.
+    /// Added ports join the send group; removed ones leave it and lose
+    /// their pane.
+    fn reload_config(&mut self) {
+        match self.hub.reload_config(&self.config_path) {
+            Ok(changes) => {
+                for change in changes {
+                    match change {
+                        ConfigChange::Added(name) => self.send_group_popup.select(name),
+                        ConfigChange::Removed(name) => {
+                            self.send_group_popup.deselect(&name);
+                            self.panes.remove_port(&name);
+                        }
+                    }
                 }
             }
+            Err(e) => self.notification_popup.show(format!(
+                "Config reload failed, keeping current ports: {e:#}"
+            )),
         }
-        Ok(())
+
+        // After the ports, so their names are checked against the new ones
+        self.load_macros();
+    }
+
+    /// Adds received data to the display panes showing its port.
+    fn push_port_data(&mut self, event: &PortEvent) {
+        let color = self.port_color(&event.port);
+        self.panes.push_data(event, color);
+    }
+
+    /// Pushes a marker line into the display for a connection transition.
+    ///
+    /// Only the first reconnect attempt is shown so that a long outage
+    /// does not flood the scrollback.
+    fn push_connection_marker(&mut self, port: &Arc<str>, state: ConnectionState) {
+        let (text, color) = match state {
+            ConnectionState::Connected => ("connected", Color::Green),
+            ConnectionState::Disconnected => ("disconnected", Color::Red),
+            ConnectionState::Reconnecting { attempt: 1 } => ("lost, reconnecting", Color::Yellow),
+            ConnectionState::Reconnecting { .. } => return,
+            ConnectionState::Closed => ("closed", Color::DarkGray),
+        };
+        self.panes.push_marker(Entry {
+            timestamp: Local::now(),
+            port: port.clone(),
+            port_color: self.port_color(port),
+            kind: EntryKind::Marker {
+                text: text.into(),
+                color,
+            },
+        });
+    }
+
+    /// Carries out the UI side of a fired trigger.
+    fn handle_trigger(&mut self, port: Arc<str>, action: TriggerAction) {
+        match action {
+            // Written by the port's reader thread
+            TriggerAction::Send(_) => {}
+            TriggerAction::Notify(message) => {
+                self.notification_popup.show(format!("{port}: {message}"));
+                for display in self.panes.displays_for(&port) {
+                    display.flash();
+                }
+                // Terminal bell
+                let mut stdout = io::stdout();
+                let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+            }
+            TriggerAction::Marker(text) => {
+                self.panes.push_marker(Entry {
+                    timestamp: Local::now(),
+                    port_color: self.port_color(&port),
+                    port,
+                    kind: EntryKind::Marker {
+                        text,
+                        color: Color::Magenta,
+                    },
+                });
+            }
+            TriggerAction::Pause => {
+                for display in self.panes.displays_for(&port) {
+                    display.pause();
+                }
+            }
+            TriggerAction::RunMacro(name) => self.run_macro(&name),
+        }
+    }
+
+    /// Looks up the display color of a port from its config.
+    fn port_color(&self, port: &str) -> Color {
+        self.hub
+            .get_config(port)
+            .map(|info| info.color.0)
+            .unwrap_or(Color::Reset)
     }
 
     /// Routes keyboard input to the appropriate handler.
@@ -236,8 +451,25 @@ impl Ui {
             return;
         }
 
+        // Edit form opens on top of the port list
+        if self.edit_port_popup.is_visible() {
+            if let Some(action) = self.edit_port_popup.handle_key(key) {
+                self.handle_edit_port_action(action);
+            }
+            return;
+        }
+
         if self.port_list_popup.visible {
-            self.port_list_popup.handle_key(key, &ports);
+            if let Some(action) = self.port_list_popup.handle_key(key, &ports) {
+                self.handle_port_list_action(action);
+            }
+            return;
+        }
+
+        if self.add_port_popup.visible {
+            if let Some(action) = self.add_port_popup.handle_key(key) {
+                self.handle_add_port_action(action);
+            }
             return;
         }
 
@@ -246,9 +478,29 @@ impl Ui {
             return;
         }
 
-        // Global keys (always available when no popup)
+        if self.macro_popup.visible {
+            if let Some(action) = self.macro_popup.handle_key(key, &self.macros) {
+                self.handle_macro_action(action);
+            }
+            return;
+        }
+
+        if self.stats_popup.visible {
+            self.stats_popup.handle_key(key);
+            return;
+        }
+
+        // Global keys (always available when no popup). `?` searches
+        // backwards in the display and is typed into the input bar; `Esc`
+        // leaves the display's visual/search mode and cancels the input
+        // bar's history search.
+        let busy = match self.focus {
+            Focus::ConfigBar => false,
+            Focus::Display => self.panes.is_modal(),
+            Focus::InputBar => self.input_bar.is_searching(),
+        };
         match key.code {
-            KeyCode::Esc => {
+            KeyCode::Esc if !busy => {
                 self.exit = true;
                 return;
             }
@@ -256,7 +508,19 @@ impl Ui {
                 self.cycle_focus();
                 return;
             }
-            KeyCode::Char('?') => {
+            KeyCode::F(n) => {
+                let bound = self
+                    .macros
+                    .iter()
+                    .find(|(_, mac)| mac.key.is_some_and(|key| key.0 == n))
+                    .map(|(name, _)| name.clone());
+                // Unbound keys go on to the focused widget
+                if let Some(name) = bound {
+                    self.run_macro(&name);
+                    return;
+                }
+            }
+            KeyCode::Char('?') if self.focus == Focus::ConfigBar => {
                 self.help_popup.toggle();
                 return;
             }
@@ -269,11 +533,14 @@ impl Ui {
                 if let Some(action) = self.config_bar.handle_key(key) {
                     match action {
                         ConfigAction::OpenPorts => self.port_list_popup.toggle(),
+                        ConfigAction::AddPort => self.add_port_popup.toggle(),
+                        ConfigAction::OpenMacros => self.macro_popup.toggle(),
+                        ConfigAction::OpenStats => self.stats_popup.toggle(),
                     }
                 }
             }
             Focus::Display => {
-                if let Some(action) = self.display.handle_key(key, self.display_height) {
+                if let Some(action) = self.panes.handle_key(key) {
                     match action {
                         DisplayAction::FocusInput => {
                             self.focus = Focus::InputBar;
@@ -290,29 +557,365 @@ impl Ui {
                         InputBarAction::OpenSendGroup => {
                             self.send_group_popup.toggle();
                         }
-                        InputBarAction::Send(text) => match text.as_str() {
-                            "/clear" => self.display.clear(),
+                        InputBarAction::Command(command) => match command.as_str() {
+                            "/clear" => self.panes.clear(),
+                            "/reload" => self.reload_config(),
                             "/help" => self.help_popup.toggle(),
+                            "/stats" => self.log_stats(),
                             "/purge" => {
                                 let _ = self.log_tx.send(LoggerEvent::Purge);
                             }
-                            _ => {
-                                let selected = self.send_group_popup.get_selected();
-                                if selected.is_empty() {
-                                    self.notification_popup.show("No ports selected");
-                                } else {
-                                    if let Err(e) = self.hub.send(&selected, Bytes::from(text)) {
-                                        self.notification_popup.show(format!("Send failed: {e}"));
-                                    }
+                            "/stop" => {
+                                for stop in self.running_macros.values() {
+                                    stop.store(true, Ordering::Relaxed);
+                                }
+                                if let Some(script) = &self.script {
+                                    script.stop.store(true, Ordering::Relaxed);
                                 }
                             }
+                            "/reset" => {
+                                let selected = self.send_group_popup.get_selected();
+                                self.control(&selected, SerialHub::reset);
+                            }
+                            _ => match command.strip_prefix("/run ") {
+                                Some(path) => self.run_script(Path::new(path.trim())),
+                                None => match parse_control(&command) {
+                                    Some(Ok(step)) => {
+                                        let selected = self.send_group_popup.get_selected();
+                                        let sequence = ControlSequence(vec![step]);
+                                        self.control(&selected, |hub, ports| {
+                                            hub.control(ports, sequence)
+                                        });
+                                    }
+                                    Some(Err(e)) => self.notification_popup.show(e.to_string()),
+                                    None => self.notification_popup.show(format!(
+                                        "Unknown command: {command} (// sends a leading /)"
+                                    )),
+                                },
+                            },
                         },
+                        InputBarAction::Send { data, line_ending } => self.send(data, line_ending),
+                        InputBarAction::Error(e) => {
+                            self.notification_popup.show(format!("Input error: {e}"));
+                        }
                     }
                 }
             }
         }
     }
 
+    /// Sends data to the ports selected in the send group.
+    fn send(&mut self, data: Bytes, line_ending: bool) {
+        let selected = self.send_group_popup.get_selected();
+        self.send_to(&selected, data, line_ending);
+    }
+
+    /// Sends data to `ports`, reporting failures as notifications.
+    fn send_to(&mut self, ports: &[Arc<str>], data: Bytes, line_ending: bool) {
+        if ports.is_empty() {
+            self.notification_popup.show("No ports selected");
+            return;
+        }
+
+        let result = if line_ending {
+            self.hub.send(ports, data)
+        } else {
+            self.hub.send_raw(ports, data)
+        };
+        if let Err(e) = result {
+            self.notification_popup.show(format!("Send failed: {e}"));
+        }
+    }
+
+    /// Queues control line steps on `ports` with `run` (`SerialHub::control`
+    /// or `SerialHub::reset`), reporting failures as notifications.
+    fn control(
+        &mut self,
+        ports: &[Arc<str>],
+        run: impl FnOnce(&SerialHub, &[Arc<str>]) -> Result<(), SerialError>,
+    ) {
+        if ports.is_empty() {
+            self.notification_popup.show("No ports selected");
+            return;
+        }
+        if let Err(e) = run(&self.hub, ports) {
+            self.notification_popup.show(format!("Control failed: {e}"));
+        }
+    }
+
+    /// Writes a snapshot of every port's stats to the logs and display as
+    /// marker lines.
+    fn log_stats(&mut self) {
+        let timestamp = Local::now();
+        for (port, stats) in self.hub.list_stats() {
+            let text = format!("stats: {stats}");
+            let _ = self.log_tx.send(LoggerEvent::Marker {
+                port: port.clone(),
+                text: text.clone(),
+                timestamp,
+            });
+            self.panes.push_marker(Entry {
+                timestamp,
+                port_color: self.port_color(&port),
+                port,
+                kind: EntryKind::Marker {
+                    text,
+                    color: Color::Cyan,
+                },
+            });
+        }
+    }
+
+    /// (Re)loads `macros.toml`; on error the previous macros are kept.
+    ///
+    /// Ports named by macros that the hub does not know are reported.
+    fn load_macros(&mut self) {
+        match macros::load_macros(&self.config_path) {
+            Ok(macros) => self.macros = macros,
+            Err(e) => {
+                self.notification_popup.show(format!("{e:#}"));
+                return;
+            }
+        }
+
+        let known: Vec<Arc<str>> = self
+            .hub
+            .list_ports()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        let unknown: Vec<String> = macros::unknown_ports(&self.macros, &known)
+            .into_iter()
+            .map(|(name, port)| format!("{name}: unknown port {port}"))
+            .collect();
+        if !unknown.is_empty() {
+            self.notification_popup
+                .show(format!("Macros: {}", unknown.join(", ")));
+        }
+    }
+
+    /// Starts a macro on a background thread.
+    ///
+    /// Steps without their own ports go to the macro's ports, or to the
+    /// send group as selected right now.
+    fn run_macro(&mut self, name: &str) {
+        let Some(mac) = self.macros.get(name) else {
+            self.notification_popup
+                .show(format!("Unknown macro: {name}"));
+            return;
+        };
+        let name: Arc<str> = name.into();
+        if self.running_macros.contains_key(&name) {
+            self.notification_popup
+                .show(format!("Macro {name} is already running"));
+            return;
+        }
+
+        let ports = mac
+            .ports
+            .clone()
+            .unwrap_or_else(|| self.send_group_popup.get_selected());
+        let (stop, _) = macro_runner::spawn(name.clone(), mac.clone(), ports, self.ui_tx.clone());
+        self.running_macros.insert(name, stop);
+    }
+
+    /// Loads a script file and runs it on a background thread.
+    ///
+    /// The script reads port data through its own hub subscription; its
+    /// sends, progress and result come back as `UiEvent`s.
+    fn run_script(&mut self, path: &Path) {
+        if let Some(script) = &self.script {
+            self.notification_popup
+                .show(format!("Script {} is already running", script.name));
+            return;
+        }
+
+        let script = match Script::load(path) {
+            Ok(script) => script,
+            Err(e) => {
+                self.notification_popup.show(format!("{e:#}"));
+                return;
+            }
+        };
+
+        let name: Arc<str> = path
+            .file_name()
+            .map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy())
+            .into();
+        let (stop, _) = runner::spawn(script, self.hub.subscribe(), self.ui_tx.clone());
+        self.script = Some(RunningScript {
+            name: name.clone(),
+            stop,
+            line: 0,
+        });
+        self.push_script_marker("started".into(), Color::Cyan);
+    }
+
+    /// Reports a script's result in the display, the logs and as a
+    /// notification.
+    fn finish_script(&mut self, outcome: Outcome) {
+        let (text, color) = match outcome {
+            Outcome::Passed(message) => (format!("PASS {message}"), Color::Green),
+            Outcome::Failed(message) => (format!("FAIL {message}"), Color::Red),
+            Outcome::Stopped => ("stopped".to_string(), Color::Yellow),
+        };
+        if let Some(script) = &self.script {
+            self.notification_popup
+                .show(format!("{}: {text}", script.name));
+        }
+        self.push_script_marker(text.trim_end().to_string(), color);
+        self.script = None;
+    }
+
+    /// Adds a line from the running script to the display and the logs,
+    /// tagged with the script name.
+    fn push_script_marker(&mut self, text: String, color: Color) {
+        let Some(script) = &self.script else {
+            return;
+        };
+        let timestamp = Local::now();
+        let _ = self.log_tx.send(LoggerEvent::Marker {
+            port: script.name.clone(),
+            text: text.clone(),
+            timestamp,
+        });
+        self.panes.push_marker(Entry {
+            timestamp,
+            port: script.name.clone(),
+            port_color: Color::Cyan,
+            kind: EntryKind::Marker { text, color },
+        });
+    }
+
+    /// Runs or stops a macro chosen in the macro popup.
+    fn handle_macro_action(&mut self, action: MacroAction) {
+        match action {
+            MacroAction::Run(name) => self.run_macro(&name),
+            MacroAction::Stop(name) => {
+                if let Some(stop) = self.running_macros.get(name.as_str()) {
+                    stop.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Applies a connect/disconnect/reopen/edit/save request from the port list.
+    fn handle_port_list_action(&mut self, action: PortListAction) {
+        let name = action.port().clone();
+        let is_down = matches!(
+            self.hub.port_state(&name),
+            Some(ConnectionState::Closed | ConnectionState::Disconnected)
+        );
+
+        let result = match action {
+            PortListAction::Toggle(_) | PortListAction::Connect(_) if is_down => {
+                self.hub.reopen(&name)
+            }
+            PortListAction::Connect(_) => Ok(()),
+            PortListAction::Toggle(_) | PortListAction::Disconnect(_) => self.hub.close(&name),
+            PortListAction::Reopen(_) => self.hub.reopen(&name),
+            PortListAction::Edit(_) => {
+                if let Some(config) = self.hub.get_config(&name) {
+                    self.edit_port_popup.open(name.clone(), config);
+                }
+                Ok(())
+            }
+            PortListAction::Save(_) => {
+                if let Some(config) = self.hub.get_config(&name) {
+                    match config::save_port(&self.config_path, &name, config) {
+                        Ok(()) => self.notification_popup.show(format!("Saved {name}")),
+                        Err(e) => self.notification_popup.show(format!("{e:#}")),
+                    }
+                }
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            self.notification_popup.show(format!("{name}: {e}"));
+        }
+    }
+
+    /// Opens a port from the add-port wizard, optionally saving it to the config.
+    ///
+    /// On failure the wizard stays open with the error shown in its form.
+    fn handle_add_port_action(&mut self, action: AddPortAction) {
+        let AddPortAction::Add { name, config, save } = action;
+
+        if self.hub.get_config(&name).is_some() {
+            self.add_port_popup
+                .set_error(format!("port {name} already exists"));
+            return;
+        }
+
+        if let Err(e) = self.hub.open(name.clone(), config.clone()) {
+            self.add_port_popup
+                .set_error(format!("failed to open: {e}"));
+            return;
+        }
+
+        if save && let Err(e) = config::save_port(&self.config_path, &name, &config) {
+            self.notification_popup.show(format!("{e:#}"));
+        } else if save {
+            self.notification_popup
+                .show(format!("Added {name} (saved)"));
+        } else {
+            self.notification_popup.show(format!("Added {name}"));
+        }
+
+        self.send_group_popup.select(name.into());
+        self.add_port_popup.close();
+    }
+
+    /// Reopens an edited port with its new settings, optionally saving them.
+    ///
+    /// A rename closes the old port, moves its send-group selection and
+    /// panes and, when saving, replaces its section in the config.
+    fn handle_edit_port_action(&mut self, action: EditPortAction) {
+        let EditPortAction::Apply {
+            original,
+            name,
+            config,
+            save,
+        } = action;
+        let renamed = *original != *name;
+
+        if renamed && self.hub.get_config(&name).is_some() {
+            self.edit_port_popup
+                .set_error(format!("port {name} already exists"));
+            return;
+        }
+
+        if renamed {
+            let _ = self.hub.remove(&original);
+            self.send_group_popup
+                .rename(&original, name.as_str().into());
+            self.panes.rename_port(&original, name.as_str().into());
+        }
+
+        let mut messages = vec![match self.hub.reconfigure(&name, config.clone()) {
+            Ok(()) => format!("Applied {name}"),
+            Err(e) => format!("{name}: {e}"),
+        }];
+
+        if save {
+            let saved = config::save_port(&self.config_path, &name, &config).and_then(|()| {
+                if renamed {
+                    config::remove_port(&self.config_path, &original)
+                } else {
+                    Ok(())
+                }
+            });
+            messages.push(match saved {
+                Ok(()) => "saved".into(),
+                Err(e) => format!("{e:#}"),
+            });
+        }
+
+        self.notification_popup.show(messages.join(", "));
+        self.edit_port_popup.close();
+    }
+
     /// Cycles focus to the next widget in order.
     ///
     /// Order: ConfigBar -> Display -> InputBar -> ConfigBar
@@ -324,3 +927,20 @@ impl Ui {
         };
     }
 }
+
+/// Parses the control line commands `/dtr [on|off]`, `/rts [on|off]` and
+/// `/break [ms]`. Without an argument the line is toggled, or the break
+/// lasts 250 ms. Returns `None` for any other command.
+fn parse_control(command: &str) -> Option<Result<ControlStep, ConfigError>> {
+    let (name, arg) = command.split_once(' ').unwrap_or((command, ""));
+    let default = match name {
+        "/dtr" | "/rts" => "toggle",
+        "/break" => "250",
+        _ => return None,
+    };
+    let arg = match arg.trim() {
+        "" => default,
+        arg => arg,
+    };
+    Some(format!("{}={arg}", &name[1..]).parse())
+}
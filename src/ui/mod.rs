@@ -1,22 +1,55 @@
 //! Terminal user interface built with Ratatui.
 //!
-//! The UI consists of three main widgets (ConfigBar, Display, InputBar)
-//! and a popup system for modal dialogs (port list, send group selection,
-//! notifications).
+//! The UI consists of three main areas (ConfigBar, display Panes, InputBar)
+//! and a popup system for modal dialogs (port list, add/edit port, send
+//! group selection, macros, stats, notifications).
 
 use std::sync::Arc;
 
-use crate::serial::PortEvent;
+use bytes::Bytes;
+
+use crate::{
+    config::{port::ControlSequence, triggers::TriggerAction},
+    script::runner::Outcome,
+    serial::{ConnectionState, ModemLines, PortEvent},
+};
 
 mod app;
 mod popup;
 mod widgets;
 
 pub use app::Ui;
-pub use popup::{HelpPopup, PortListPopup, SendGroupPopup};
+pub use popup::{
+    AddPortPopup, EditPortPopup, HelpPopup, MacroPopup, PortListPopup, SendGroupPopup, StatsPopup,
+};
 
 /// Events sent to the UI from background components.
 pub enum UiEvent {
     PortData(Arc<PortEvent>),
     ShowNotification(Arc<str>),
+    /// A port's connection state changed (e.g. device unplugged)
+    ConnectionChanged(Arc<str>, ConnectionState),
+    /// A port's modem status lines were first read or changed
+    ModemChanged(Arc<str>, ModemLines),
+    /// The config file was modified on disk
+    ConfigChanged,
+    /// Data to send from a background task (e.g. a macro step)
+    Send {
+        ports: Vec<Arc<str>>,
+        data: Bytes,
+        /// Append each port's `tx_line_ending`
+        line_ending: bool,
+    },
+    /// A macro finished playing or was stopped
+    MacroFinished(Arc<str>),
+    /// The running script reached this source line
+    ScriptProgress(usize),
+    /// A `log` line from the running script
+    ScriptLog(Arc<str>),
+    /// The running script passed, failed or was stopped
+    ScriptFinished(Outcome),
+    /// A trigger matched data on a port; sends are already done by the reader
+    TriggerFired(Arc<str>, TriggerAction),
+    /// Control line steps ran on a port (toggles resolved); already logged
+    ControlLines(Arc<str>, ControlSequence),
 }
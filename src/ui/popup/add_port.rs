@@ -0,0 +1,199 @@
+//! Add-port wizard.
+//!
+//! Step 1 lists the devices reported by `serialport::available_ports()`
+//! (with USB VID/PID, product and serial number). Step 2 shows a
+//! `PortForm` pre-filled with the chosen device path.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+};
+use serialport::SerialPortInfo;
+
+use crate::{config::PortConfig, serial::port::device_details};
+
+use super::{
+    Popup,
+    port_form::{FormAction, PortForm},
+    select_next, select_prev,
+};
+
+/// Actions the add-port popup can request.
+pub enum AddPortAction {
+    /// Open a new port; `save` also writes it to `ports.toml`
+    Add {
+        name: String,
+        config: PortConfig,
+        save: bool,
+    },
+}
+
+/// Which step of the wizard is shown.
+enum Step {
+    /// Picking a device from the system list
+    Devices,
+    /// Filling in the port settings
+    Form(PortForm),
+}
+
+/// Popup for adding a new port at runtime.
+pub struct AddPortPopup {
+    /// Helper for centered positioning
+    popup: Popup,
+    /// Devices found when the popup was opened
+    devices: Vec<SerialPortInfo>,
+    /// Selection in the device list (last row is manual entry)
+    list_state: ListState,
+    step: Step,
+    /// Whether the popup is currently shown
+    pub visible: bool,
+}
+
+impl AddPortPopup {
+    /// Creates a new hidden add-port popup.
+    ///
+    /// Uses 60% width, 60% height of the screen.
+    pub fn new() -> Self {
+        Self {
+            popup: Popup::new(60, 60),
+            devices: Vec::new(),
+            list_state: ListState::default().with_selected(Some(0)),
+            step: Step::Devices,
+            visible: false,
+        }
+    }
+
+    /// Toggles visibility, rescanning system devices on open.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        if self.visible {
+            self.devices = serialport::available_ports().unwrap_or_default();
+            self.list_state.select(Some(0));
+            self.step = Step::Devices;
+        }
+    }
+
+    /// Closes the popup after a successful add.
+    pub fn close(&mut self) {
+        self.visible = false;
+        self.step = Step::Devices;
+    }
+
+    /// Shows an error in the form (e.g. the port failed to open).
+    pub fn set_error(&mut self, error: impl Into<String>) {
+        if let Step::Form(form) = &mut self.step {
+            form.set_error(error);
+        }
+    }
+
+    /// Formats a device as `path  USB vid:pid  product  SN serial`.
+    fn describe(info: &SerialPortInfo) -> Line<'static> {
+        Line::from(vec![
+            Span::raw(format!("{}  ", info.port_name)),
+            Span::styled(
+                device_details(&info.port_type),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])
+    }
+
+    /// Builds the form for the highlighted device (or a blank one for manual entry).
+    fn start_form(&mut self) {
+        let device = self.list_state.selected().and_then(|i| self.devices.get(i));
+
+        let (name, config) = match device {
+            Some(info) => {
+                let name = info
+                    .port_name
+                    .rsplit(['/', '\\'])
+                    .next()
+                    .unwrap_or(&info.port_name)
+                    .to_string();
+                let config = PortConfig {
+                    path: info.port_name.clone().into(),
+                    ..Default::default()
+                };
+                (name, config)
+            }
+            None => (String::new(), PortConfig::default()),
+        };
+
+        self.step = Step::Form(PortForm::new(&name, &config));
+    }
+
+    /// Renders the current wizard step.
+    pub fn render(&mut self, frame: &mut Frame) {
+        if !self.visible {
+            return;
+        }
+
+        let area = self.popup.area(frame.area());
+        self.popup.clear(frame, area);
+
+        match &self.step {
+            Step::Form(form) => form.render(frame, area, " Add Port "),
+            Step::Devices => {
+                let mut items: Vec<ListItem> = self
+                    .devices
+                    .iter()
+                    .map(|info| ListItem::new(Self::describe(info)))
+                    .collect();
+                items.push(ListItem::new(Span::styled(
+                    "(enter path manually)",
+                    Style::default().fg(Color::Yellow),
+                )));
+
+                let list = List::new(items)
+                    .block(
+                        Block::default()
+                            .title(" Add Port: select device ")
+                            .title_bottom(" Enter select  r rescan  Esc close ")
+                            .borders(Borders::ALL)
+                            .border_style(Style::default().fg(Color::White)),
+                    )
+                    .highlight_style(Style::default().bg(Color::DarkGray));
+
+                frame.render_stateful_widget(list, area, &mut self.list_state);
+            }
+        }
+    }
+
+    /// Handles key input when this popup is visible.
+    ///
+    /// Device step:
+    /// - `Esc` -> Close popup
+    /// - `Up/k` / `Down/j` -> Navigate devices
+    /// - `r` -> Rescan devices
+    /// - `Enter` -> Continue to the settings form
+    ///
+    /// Form step: see `PortForm::handle_key`; `Esc` returns to the device list.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<AddPortAction> {
+        // Rows: one per device plus the manual entry row
+        let len = self.devices.len() + 1;
+
+        match &mut self.step {
+            Step::Devices => match key.code {
+                KeyCode::Esc => self.visible = false,
+                KeyCode::Up | KeyCode::Char('k') => select_prev(&mut self.list_state, len),
+                KeyCode::Down | KeyCode::Char('j') => select_next(&mut self.list_state, len),
+                KeyCode::Char('r') => {
+                    self.devices = serialport::available_ports().unwrap_or_default();
+                    self.list_state.select(Some(0));
+                }
+                KeyCode::Enter => self.start_form(),
+                _ => {}
+            },
+            Step::Form(form) => match form.handle_key(key)? {
+                FormAction::Cancel => self.step = Step::Devices,
+                FormAction::Submit { save } => match form.to_config() {
+                    Ok((name, config)) => return Some(AddPortAction::Add { name, config, save }),
+                    Err(e) => form.set_error(e),
+                },
+            },
+        }
+        None
+    }
+}
@@ -0,0 +1,104 @@
+//! Edit-port popup.
+//!
+//! Shows a `PortForm` for the port highlighted in the port list. Applying
+//! reopens the port with the new settings; `Ctrl+s` also saves them.
+
+use std::sync::Arc;
+
+use crossterm::event::KeyEvent;
+use ratatui::Frame;
+
+use crate::config::PortConfig;
+
+use super::{
+    Popup,
+    port_form::{FormAction, PortForm},
+};
+
+/// Actions the edit-port popup can request.
+pub enum EditPortAction {
+    /// Reopen `original` as `name` with `config`; `save` also writes `ports.toml`
+    Apply {
+        original: Arc<str>,
+        name: String,
+        config: PortConfig,
+        save: bool,
+    },
+}
+
+/// Popup for changing an existing port's settings.
+pub struct EditPortPopup {
+    /// Helper for centered positioning
+    popup: Popup,
+    /// Port being edited and its form, `None` while hidden
+    editing: Option<(Arc<str>, PortForm)>,
+}
+
+impl EditPortPopup {
+    /// Creates a new hidden edit-port popup.
+    ///
+    /// Uses 60% width, 60% height of the screen.
+    pub fn new() -> Self {
+        Self {
+            popup: Popup::new(60, 60),
+            editing: None,
+        }
+    }
+
+    /// Returns true if the popup is currently shown.
+    pub fn is_visible(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Shows the form pre-filled with the port's current settings.
+    pub fn open(&mut self, name: Arc<str>, config: &PortConfig) {
+        let form = PortForm::new(&name, config);
+        self.editing = Some((name, form));
+    }
+
+    /// Hides the popup after the changes were applied.
+    pub fn close(&mut self) {
+        self.editing = None;
+    }
+
+    /// Shows an error in the form (e.g. the port failed to reopen).
+    pub fn set_error(&mut self, error: impl Into<String>) {
+        if let Some((_, form)) = &mut self.editing {
+            form.set_error(error);
+        }
+    }
+
+    /// Renders the form.
+    pub fn render(&self, frame: &mut Frame) {
+        let Some((name, form)) = &self.editing else {
+            return;
+        };
+
+        let area = self.popup.area(frame.area());
+        self.popup.clear(frame, area);
+        form.render(frame, area, &format!(" Edit {name} "));
+    }
+
+    /// Handles key input when this popup is visible.
+    ///
+    /// See `PortForm::handle_key`; `Esc` discards the changes.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<EditPortAction> {
+        let (original, form) = self.editing.as_mut()?;
+
+        match form.handle_key(key)? {
+            FormAction::Cancel => self.editing = None,
+            FormAction::Submit { save } => match form.to_config() {
+                Ok((name, config)) => {
+                    return Some(EditPortAction::Apply {
+                        original: original.clone(),
+                        name,
+                        config,
+                        save,
+                    });
+                }
+                Err(e) => form.set_error(e),
+            },
+        }
+        None
+    }
+}
@@ -64,7 +64,7 @@ impl HelpPopup {
             ]),
             Line::from(vec![
                 Span::styled("    ?         ", key),
-                Span::styled("Toggle this help screen", desc),
+                Span::styled("Toggle help from the config bar (/help in input bar)", desc),
             ]),
             Line::from(""),
             Line::from(Span::styled("  Config Bar", header)),
@@ -76,6 +76,18 @@ impl HelpPopup {
                 Span::styled("    a         ", key),
                 Span::styled("Add new port", desc),
             ]),
+            Line::from(vec![
+                Span::styled("    m         ", key),
+                Span::styled("Open macros (Enter run, s stop)", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    s         ", key),
+                Span::styled("Open per-port throughput and error stats", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    F1..F12   ", key),
+                Span::styled("Run the macro bound to the key", desc),
+            ]),
             Line::from(""),
             Line::from(Span::styled("  Display", header)),
             Line::from(vec![
@@ -99,8 +111,8 @@ impl HelpPopup {
                 Span::styled("Jump to top", desc),
             ]),
             Line::from(vec![
-                Span::styled("    G         ", key),
-                Span::styled("Jump to bottom", desc),
+                Span::styled("    G / F     ", key),
+                Span::styled("Jump to bottom, follow new data (moving up pauses)", desc),
             ]),
             Line::from(vec![
                 Span::styled("    v / V     ", key),
@@ -111,8 +123,19 @@ impl HelpPopup {
                 Span::styled("Yank selection to clipboard", desc),
             ]),
             Line::from(vec![
-                Span::styled("    /         ", key),
-                Span::styled("Search", desc),
+                Span::styled("    x         ", key),
+                Span::styled("Cycle view: text / hex dump / escaped", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    t         ", key),
+                Span::styled(
+                    "Cycle timestamps: time / µs / date / relative / delta / per-port delta / off",
+                    desc,
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("    / / ?     ", key),
+                Span::styled("Regex search down / up (smart-case, Esc cancels)", desc),
             ]),
             Line::from(vec![
                 Span::styled("    n         ", key),
@@ -120,7 +143,23 @@ impl HelpPopup {
             ]),
             Line::from(vec![
                 Span::styled("    N         ", key),
-                Span::styled("Previous search match", desc),
+                Span::styled("Next match in the other direction", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    &         ", key),
+                Span::styled("Filter: &re, &!re hides, &@com1,com2; & clears", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    ] / [     ", key),
+                Span::styled("Focus next / previous pane", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    z         ", key),
+                Span::styled("Zoom the focused pane / show all panes", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    L         ", key),
+                Span::styled("Cycle split: side by side / stacked / grid", desc),
             ]),
             Line::from(vec![
                 Span::styled("    Enter     ", key),
@@ -136,14 +175,74 @@ impl HelpPopup {
                 Span::styled("    Enter     ", key),
                 Span::styled("Send text to selected ports", desc),
             ]),
+            Line::from(vec![
+                Span::styled("    Alt+Enter ", key),
+                Span::styled("Send without line ending", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    ↑ / ↓     ", key),
+                Span::styled("Previous / next history entry", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    Ctrl+r    ", key),
+                Span::styled("Reverse search history (Esc cancels)", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    ←→ Home End", key),
+                Span::styled(" Move cursor (Ctrl+a/e, Ctrl/Alt word)", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    Ctrl+w/u/k", key),
+                Span::styled("  Delete word / to start / to end", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    Ctrl+x    ", key),
+                Span::styled("Toggle hex input (7F 00 A5)", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    \\x03 \\r   ", key),
+                Span::styled("Escapes: \\r \\n \\t \\0 \\\\ \\xHH", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    //text    ", key),
+                Span::styled("Send text starting with /", desc),
+            ]),
             Line::from(vec![
                 Span::styled("    /clear    ", key),
                 Span::styled("Clear display", desc),
             ]),
+            Line::from(vec![
+                Span::styled("    /reload   ", key),
+                Span::styled("Reload ports.toml (also automatic on change)", desc),
+            ]),
             Line::from(vec![
                 Span::styled("    /purge    ", key),
                 Span::styled("Purge log files", desc),
             ]),
+            Line::from(vec![
+                Span::styled("    /stop     ", key),
+                Span::styled("Stop running macros and script", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    /run FILE ", key),
+                Span::styled("Run an expect/send script", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    /stats    ", key),
+                Span::styled("Write every port's stats to the logs", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    /dtr /rts ", key),
+                Span::styled("Set DTR / RTS on|off, toggle without argument", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    /break MS ", key),
+                Span::styled("Send a BREAK (default 250 ms)", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    /reset    ", key),
+                Span::styled("Run each port's reset_sequence", desc),
+            ]),
             Line::from(""),
             Line::from(Span::styled("  Popups", header)),
             Line::from(vec![
@@ -158,6 +257,46 @@ impl HelpPopup {
                 Span::styled("    Esc       ", key),
                 Span::styled("Close popup", desc),
             ]),
+            Line::from(""),
+            Line::from(Span::styled("  Port List", header)),
+            Line::from(vec![
+                Span::styled("    Enter     ", key),
+                Span::styled("Connect / disconnect port", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    c / d     ", key),
+                Span::styled("Connect / disconnect", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    r         ", key),
+                Span::styled("Reopen port", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    e         ", key),
+                Span::styled("Edit port settings", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    s         ", key),
+                Span::styled("Save port settings to ports.toml", desc),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled("  Port Form", header)),
+            Line::from(vec![
+                Span::styled("    ↑↓ / Tab  ", key),
+                Span::styled("Move between fields", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    ←→        ", key),
+                Span::styled("Change value", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    Enter     ", key),
+                Span::styled("Apply", desc),
+            ]),
+            Line::from(vec![
+                Span::styled("    Ctrl+s    ", key),
+                Span::styled("Apply and save to ports.toml", desc),
+            ]),
         ];
 
         let paragraph = Paragraph::new(lines)
@@ -0,0 +1,146 @@
+//! Macro popup for running and stopping macros from `macros.toml`.
+//!
+//! Lists every macro with its function key and description, and marks
+//! the ones currently playing.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, atomic::AtomicBool},
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+};
+
+use crate::config::macros::Macro;
+
+use super::{Popup, select_next, select_prev};
+
+/// Actions the macro popup can request.
+pub enum MacroAction {
+    /// Start playing the named macro
+    Run(String),
+    /// Stop the named macro if it is playing
+    Stop(String),
+}
+
+/// Popup listing the loaded macros.
+pub struct MacroPopup {
+    /// Helper for centered positioning
+    popup: Popup,
+    /// Current cursor position in the list
+    list_state: ListState,
+    /// Whether the popup is currently shown
+    pub visible: bool,
+}
+
+impl MacroPopup {
+    /// Creates a new hidden macro popup.
+    ///
+    /// Uses 50% width, 50% height of the screen.
+    pub fn new() -> Self {
+        Self {
+            popup: Popup::new(50, 50),
+            list_state: ListState::default().with_selected(Some(0)),
+            visible: false,
+        }
+    }
+
+    /// Toggles visibility, resetting cursor on open.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        if self.visible {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    /// Renders the macro list.
+    ///
+    /// Each item shows: `F1  name  description` plus `[running]` while playing.
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        macros: &BTreeMap<String, Macro>,
+        running: &HashMap<Arc<str>, Arc<AtomicBool>>,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let area = self.popup.area(frame.area());
+        self.popup.clear(frame, area);
+
+        let items: Vec<ListItem> = if macros.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "No macros (see macros.toml next to ports.toml)",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            macros
+                .iter()
+                .map(|(name, mac)| {
+                    let key = mac.key.map(|k| format!("F{}", k.0)).unwrap_or_default();
+                    let mut spans = vec![
+                        Span::styled(format!("{key:<4}"), Style::default().fg(Color::Yellow)),
+                        Span::raw(name.clone()),
+                        Span::styled(
+                            format!("  {}", mac.description),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    ];
+                    if running.contains_key(name.as_str()) {
+                        spans.push(Span::styled(
+                            "  [running]",
+                            Style::default().fg(Color::Green),
+                        ));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect()
+        };
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(" Macros ")
+                    .title_bottom(" Enter run  s stop  Esc close ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::White)),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray));
+
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    /// Handles key input when this popup is visible.
+    ///
+    /// - `Esc` -> Close popup
+    /// - `Up/k` / `Down/j` -> Navigate macros
+    /// - `Enter` -> Run highlighted macro
+    /// - `s` -> Stop highlighted macro
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        macros: &BTreeMap<String, Macro>,
+    ) -> Option<MacroAction> {
+        let selected = self
+            .list_state
+            .selected()
+            .and_then(|i| macros.keys().nth(i))
+            .cloned();
+
+        match key.code {
+            KeyCode::Esc => self.visible = false,
+            KeyCode::Up | KeyCode::Char('k') => select_prev(&mut self.list_state, macros.len()),
+            KeyCode::Down | KeyCode::Char('j') => select_next(&mut self.list_state, macros.len()),
+            KeyCode::Enter => return selected.map(MacroAction::Run),
+            KeyCode::Char('s') => return selected.map(MacroAction::Stop),
+            _ => {}
+        }
+        None
+    }
+}
@@ -4,15 +4,24 @@
 //! input while visible. They don't store port data - it's passed in
 //! during render/handle_key to stay in sync with the serial manager.
 
+mod add_port;
+mod edit_port;
 mod help;
+mod macros;
 mod notification;
+mod port_form;
 mod port_list;
 mod send_group;
+mod stats;
 
+pub use add_port::{AddPortAction, AddPortPopup};
+pub use edit_port::{EditPortAction, EditPortPopup};
 pub use help::HelpPopup;
+pub use macros::{MacroAction, MacroPopup};
 pub use notification::Notification;
-pub use port_list::PortListPopup;
+pub use port_list::{PortListAction, PortListPopup};
 pub use send_group::SendGroupPopup;
+pub use stats::StatsPopup;
 
 use ratatui::{
     Frame,
@@ -23,7 +32,8 @@ use ratatui::{
 /// Helper for creating centered popup overlays.
 ///
 /// Calculates a centered rectangle based on percentage of screen size.
-/// Used by `PortListPopup` and `SendGroupPopup`.
+/// Used by `PortListPopup`, `SendGroupPopup`, `AddPortPopup`, `EditPortPopup`,
+/// `MacroPopup` and `StatsPopup`.
 pub struct Popup {
     /// Width as percentage of screen (0-100)
     width_percent: u16,
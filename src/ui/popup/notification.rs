@@ -1,9 +1,12 @@
 //! Toast-style notification overlay.
 //!
-//! Shows brief messages in the top-right corner with automatic
+//! Shows brief messages stacked in the top-right corner with automatic
 //! dismissal based on message length.
 
-use std::time::{Duration, Instant};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 use ratatui::{
     Frame,
@@ -12,94 +15,102 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph},
 };
 
-/// Auto-dismissing notification toast.
-///
-/// Appears in the top-right corner and fades after a calculated
-/// duration (longer messages stay longer).
-pub struct Notification {
-    /// Current message being shown (None = hidden)
-    message: Option<String>,
+/// A single toast message.
+struct Toast {
+    message: String,
     /// When the message was shown (for timing dismissal)
-    shown_at: Option<Instant>,
-    /// How long to show the current message
+    shown_at: Instant,
+    /// How long to show the message
     duration: Duration,
 }
 
+/// Auto-dismissing notification toasts.
+///
+/// Each toast appears in the top-right corner, below any toasts that
+/// are still showing, and fades after a calculated duration (longer
+/// messages stay longer).
+pub struct Notification {
+    /// Toasts currently shown, oldest first
+    toasts: VecDeque<Toast>,
+}
+
 impl Notification {
     /// Base display time in milliseconds
     const BASE_MS: u64 = 1500;
     /// Additional milliseconds per character in the message
     const MS_PER_CHAR: u64 = 50;
+    /// Maximum toasts shown at once; the oldest is dropped beyond this
+    const MAX_TOASTS: usize = 5;
 
     /// Creates a new hidden notification.
     pub fn new() -> Self {
         Self {
-            message: None,
-            shown_at: None,
-            duration: Duration::ZERO,
+            toasts: VecDeque::new(),
         }
     }
 
     /// Shows a notification message.
     ///
     /// Duration is calculated as `BASE_MS + (char_count * MS_PER_CHAR)`.
-    /// Replaces any existing notification.
+    /// Stacks below any messages still showing.
     pub fn show(&mut self, msg: impl Into<String>) {
-        let msg = msg.into();
-        let duration_ms = Self::BASE_MS + (msg.len() as u64 * Self::MS_PER_CHAR);
-        self.duration = Duration::from_millis(duration_ms);
-        self.message = Some(msg);
-        self.shown_at = Some(Instant::now());
-    }
+        let message = msg.into();
+        let duration_ms = Self::BASE_MS + (message.len() as u64 * Self::MS_PER_CHAR);
 
-    /// Immediately hides the notification.
-    fn dismiss(&mut self) {
-        self.message = None;
-        self.shown_at = None;
+        if self.toasts.len() >= Self::MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            message,
+            shown_at: Instant::now(),
+            duration: Duration::from_millis(duration_ms),
+        });
     }
 
     /// Returns true if a notification is currently shown.
     pub fn is_visible(&self) -> bool {
-        self.message.is_some()
+        !self.toasts.is_empty()
     }
 
-    /// Checks if the notification should dismiss.
+    /// Drops toasts whose time is up.
     ///
     /// Call this each frame (or during render) to handle timing.
     pub fn tick(&mut self) {
-        if let Some(shown_at) = self.shown_at {
-            if shown_at.elapsed() >= self.duration {
-                self.dismiss();
-            }
-        }
+        self.toasts
+            .retain(|toast| toast.shown_at.elapsed() < toast.duration);
     }
 
-    /// Renders the notification in the top-right corner.
+    /// Renders the toasts in the top-right corner, one below the other.
     ///
-    /// Width adjusts to fit the message. Clears the area behind it.
+    /// Width adjusts to fit each message. Clears the area behind them.
     pub fn render(&mut self, frame: &mut Frame) {
         // Check for auto-dismiss
         self.tick();
 
-        let Some(msg) = &self.message else { return };
-
-        // Calculate size: message + padding + borders
-        let width = (msg.len() as u16 + 4).min(frame.area().width);
-        let area = Rect {
-            x: frame.area().width.saturating_sub(width),
-            y: 0,
-            width,
-            height: 3,
-        };
+        let frame_area = frame.area();
+        for (i, toast) in self.toasts.iter().enumerate() {
+            // Calculate size: message + padding + borders
+            let width = (toast.message.len() as u16 + 4).min(frame_area.width);
+            let y = i as u16 * 3;
+            if y + 3 > frame_area.height {
+                break;
+            }
+            let area = Rect {
+                x: frame_area.width.saturating_sub(width),
+                y,
+                width,
+                height: 3,
+            };
 
-        // Clear background and render
-        frame.render_widget(Clear, area);
+            // Clear background and render
+            frame.render_widget(Clear, area);
 
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan));
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan));
 
-        let text = Paragraph::new(msg.as_str()).block(block);
-        frame.render_widget(text, area);
+            let text = Paragraph::new(toast.message.as_str()).block(block);
+            frame.render_widget(text, area);
+        }
     }
 }
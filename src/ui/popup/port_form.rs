@@ -0,0 +1,309 @@
+//! Port settings form shared by the add-port and edit-port popups.
+//!
+//! Each `PortConfig` field is one row. Text rows take typed input,
+//! choice rows cycle through their options with Left/Right, and toggle
+//! rows flip with Left/Right or Space. Values are kept as strings and
+//! parsed with the config types' `FromStr` on submit, so the form
+//! reports exactly the same errors as loading `ports.toml` would.
+
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color as RatatuiColor, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    config::{
+        PortConfig,
+        port::{ControlSequence, DataBits, FlowControl, LineEnding, Parity, RxDelimiter, StopBits},
+        triggers::TRIGGERS_KEY,
+    },
+    types::color::Color,
+};
+
+/// Common baud rates offered by the baud rate row.
+const BAUD_RATES: &[&str] = &[
+    "300", "1200", "2400", "4800", "9600", "19200", "38400", "57600", "115200", "230400", "460800",
+    "921600",
+];
+const DATA_BITS: &[&str] = &["5", "6", "7", "8"];
+const STOP_BITS: &[&str] = &["1", "2"];
+const PARITIES: &[&str] = &["none", "odd", "even"];
+const FLOW_CONTROLS: &[&str] = &["none", "software", "hardware"];
+const LINE_ENDINGS: &[&str] = &["lf", "cr", "crlf"];
+const RX_DELIMITERS: &[&str] = &["lf", "cr", "crlf", "nul"];
+const COLORS: &[&str] = &[
+    "reset", "white", "gray", "red", "green", "yellow", "blue", "magenta", "cyan",
+];
+
+/// How a row is edited.
+enum FieldKind {
+    /// Free text typed by the user
+    Text,
+    /// Cycles through a fixed list; free text is also accepted
+    Choice(&'static [&'static str]),
+    /// On/off
+    Toggle,
+}
+
+/// A single row of the form.
+struct Field {
+    /// Config key this row edits
+    key: &'static str,
+    kind: FieldKind,
+    value: String,
+}
+
+impl Field {
+    fn text(key: &'static str, value: impl ToString) -> Self {
+        Self {
+            key,
+            kind: FieldKind::Text,
+            value: value.to_string(),
+        }
+    }
+
+    fn choice(key: &'static str, options: &'static [&'static str], value: impl ToString) -> Self {
+        Self {
+            key,
+            kind: FieldKind::Choice(options),
+            value: value.to_string(),
+        }
+    }
+
+    fn toggle(key: &'static str, value: bool) -> Self {
+        Self {
+            key,
+            kind: FieldKind::Toggle,
+            value: value.to_string(),
+        }
+    }
+
+    /// Moves a choice forward/backward or flips a toggle.
+    fn cycle(&mut self, forward: bool) {
+        match self.kind {
+            FieldKind::Text => {}
+            FieldKind::Toggle => {
+                self.value = (self.value != "true").to_string();
+            }
+            FieldKind::Choice(options) => {
+                let len = options.len();
+                let next = match options
+                    .iter()
+                    .position(|o| o.eq_ignore_ascii_case(&self.value))
+                {
+                    Some(i) if forward => (i + 1) % len,
+                    Some(i) => (i + len - 1) % len,
+                    None => 0,
+                };
+                self.value = options[next].to_string();
+            }
+        }
+    }
+}
+
+/// Result of a key press in the form.
+pub enum FormAction {
+    /// User confirmed; `save` requests writing the section to `ports.toml`
+    Submit { save: bool },
+    /// User backed out
+    Cancel,
+}
+
+/// Editable form over every `PortConfig` field plus the port name.
+pub struct PortForm {
+    fields: Vec<Field>,
+    /// Highlighted row
+    selected: usize,
+    /// Validation or apply error shown under the rows
+    error: Option<String>,
+}
+
+impl PortForm {
+    /// Creates a form pre-filled with `name` and `config`.
+    pub fn new(name: &str, config: &PortConfig) -> Self {
+        Self {
+            fields: vec![
+                Field::text("name", name),
+                Field::text("path", config.path.display()),
+                Field::choice("baud_rate", BAUD_RATES, config.baud_rate),
+                Field::choice("data_bits", DATA_BITS, config.data_bits.as_u8()),
+                Field::choice("stop_bits", STOP_BITS, config.stop_bits.as_u8()),
+                Field::choice("parity", PARITIES, config.parity.as_str()),
+                Field::choice("flow_control", FLOW_CONTROLS, config.flow_control.as_str()),
+                Field::choice("rx_delimiter", RX_DELIMITERS, &config.rx_delimiter),
+                Field::choice(
+                    "tx_line_ending",
+                    LINE_ENDINGS,
+                    config.tx_line_ending.as_str(),
+                ),
+                Field::toggle("local_echo", config.local_echo),
+                Field::choice("color", COLORS, &config.color),
+                Field::toggle("reconnect", config.reconnect),
+                Field::text("reconnect_interval_ms", config.reconnect_interval_ms),
+                Field::text(
+                    "reconnect_max_interval_ms",
+                    config.reconnect_max_interval_ms,
+                ),
+                Field::text("reset_sequence", &config.reset_sequence),
+                Field::text("flush_timeout_ms", config.flush_timeout_ms),
+            ],
+            selected: 0,
+            error: None,
+        }
+    }
+
+    /// Shows an error under the form (e.g. the port failed to open).
+    pub fn set_error(&mut self, error: impl Into<String>) {
+        self.error = Some(error.into());
+    }
+
+    fn value(&self, key: &str) -> &str {
+        self.fields
+            .iter()
+            .find(|f| f.key == key)
+            .map(|f| f.value.trim())
+            .unwrap_or_default()
+    }
+
+    /// Parses a row with the config type's `FromStr`.
+    fn parse<T>(&self, key: &str) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.value(key).parse().map_err(|e| format!("{key}: {e}"))
+    }
+
+    /// Validates the rows and builds the port name and config.
+    pub fn to_config(&self) -> Result<(String, PortConfig), String> {
+        let name = self.value("name");
+        if name.is_empty() {
+            return Err("name: must not be empty".into());
+        }
+        if name == TRIGGERS_KEY {
+            return Err(format!("name: {TRIGGERS_KEY} is reserved"));
+        }
+        if self.value("path").is_empty() {
+            return Err("path: must not be empty".into());
+        }
+
+        let config = PortConfig {
+            path: self.value("path").into(),
+            baud_rate: self.parse("baud_rate")?,
+            data_bits: self.parse::<DataBits>("data_bits")?,
+            stop_bits: self.parse::<StopBits>("stop_bits")?,
+            parity: self.parse::<Parity>("parity")?,
+            flow_control: self.parse::<FlowControl>("flow_control")?,
+            rx_delimiter: self.parse::<RxDelimiter>("rx_delimiter")?,
+            tx_line_ending: self.parse::<LineEnding>("tx_line_ending")?,
+            local_echo: self.parse("local_echo")?,
+            color: self.parse::<Color>("color")?,
+            reconnect: self.parse("reconnect")?,
+            reconnect_interval_ms: self.parse("reconnect_interval_ms")?,
+            reconnect_max_interval_ms: self.parse("reconnect_max_interval_ms")?,
+            reset_sequence: self.parse::<ControlSequence>("reset_sequence")?,
+            flush_timeout_ms: self.parse("flush_timeout_ms")?,
+        };
+        Ok((name.to_string(), config))
+    }
+
+    /// Renders the rows, error line and key hints inside `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect, title: &str) {
+        let block = Block::default()
+            .title(title)
+            .title_bottom(" ↑↓ field  ←→ change  Enter apply  Ctrl+s apply & save  Esc back ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(RatatuiColor::White));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [rows_area, error_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(inner);
+
+        let label_width = self.fields.iter().map(|f| f.key.len()).max().unwrap_or(0);
+        let label_style = Style::default().fg(RatatuiColor::Cyan);
+        let selected_style = Style::default().bg(RatatuiColor::DarkGray);
+
+        let lines: Vec<Line> = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let focused = i == self.selected;
+                let value = match field.kind {
+                    FieldKind::Text if focused => format!("{}_", field.value),
+                    FieldKind::Text => field.value.clone(),
+                    FieldKind::Choice(_) | FieldKind::Toggle => format!("◀ {} ▶", field.value),
+                };
+                let line = Line::from(vec![
+                    Span::styled(format!(" {:label_width$}  ", field.key), label_style),
+                    Span::raw(value),
+                ]);
+                if focused {
+                    line.style(selected_style)
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        // Keep the highlighted row visible on small terminals
+        let scroll = (self.selected + 1).saturating_sub(rows_area.height as usize);
+        frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), rows_area);
+
+        if let Some(error) = &self.error {
+            frame.render_widget(
+                Paragraph::new(error.as_str()).style(Style::default().fg(RatatuiColor::Red)),
+                error_area,
+            );
+        }
+    }
+
+    /// Handles key input while the form is shown.
+    ///
+    /// - `Up/Down/Tab/BackTab` -> Move between rows
+    /// - `Left/Right` -> Cycle choice / flip toggle
+    /// - `Space` -> Flip toggle (or type a space)
+    /// - Characters / `Backspace` -> Edit text (and choice) rows
+    /// - `Enter` -> Submit
+    /// - `Ctrl+s` -> Submit and save to config
+    /// - `Esc` -> Cancel
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<FormAction> {
+        let field = &mut self.fields[self.selected];
+        match (key.modifiers, key.code) {
+            (_, KeyCode::Esc) => return Some(FormAction::Cancel),
+            (KeyModifiers::CONTROL, KeyCode::Char('s')) => {
+                return Some(FormAction::Submit { save: true });
+            }
+            (_, KeyCode::Enter) => return Some(FormAction::Submit { save: false }),
+            (_, KeyCode::Up | KeyCode::BackTab) => {
+                self.selected = self
+                    .selected
+                    .checked_sub(1)
+                    .unwrap_or(self.fields.len() - 1);
+            }
+            (_, KeyCode::Down | KeyCode::Tab) => {
+                self.selected = (self.selected + 1) % self.fields.len();
+            }
+            (_, KeyCode::Left) => field.cycle(false),
+            (_, KeyCode::Right) => field.cycle(true),
+            (_, KeyCode::Char(' ')) if matches!(field.kind, FieldKind::Toggle) => {
+                field.cycle(true);
+            }
+            (_, KeyCode::Char(c)) if !matches!(field.kind, FieldKind::Toggle) => {
+                field.value.push(c);
+            }
+            (_, KeyCode::Backspace) if !matches!(field.kind, FieldKind::Toggle) => {
+                field.value.pop();
+            }
+            _ => {}
+        }
+        None
+    }
+}
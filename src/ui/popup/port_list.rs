@@ -1,7 +1,9 @@
-//! Port list popup for viewing connected ports.
+//! Port list popup for viewing and controlling ports.
 //!
-//! Shows all configured ports with their status (connected indicator)
-//! and baud rate. Arrow keys navigate, Enter selects.
+//! Shows all configured ports with their connection state,
+//! baud rate, framing and flow control, plus LEDs for the modem status
+//! lines of devices that report them. Arrow keys navigate, Enter
+//! connects or disconnects the highlighted port.
 
 use std::sync::Arc;
 
@@ -13,10 +15,43 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState},
 };
 
-use crate::config::PortConfig;
+use crate::{
+    config::PortConfig,
+    serial::{ConnectionState, ModemLines},
+};
 
 use super::{Popup, select_next, select_prev};
 
+/// Actions the port list can request.
+pub enum PortListAction {
+    /// Disconnect if connected, connect if closed
+    Toggle(Arc<str>),
+    /// Open a closed port
+    Connect(Arc<str>),
+    /// Close the port, releasing the device
+    Disconnect(Arc<str>),
+    /// Close and open again with the same config
+    Reopen(Arc<str>),
+    /// Open the edit form for the port
+    Edit(Arc<str>),
+    /// Write the port's current settings to `ports.toml`
+    Save(Arc<str>),
+}
+
+impl PortListAction {
+    /// Returns the name of the port this action targets.
+    pub fn port(&self) -> &Arc<str> {
+        match self {
+            PortListAction::Toggle(name)
+            | PortListAction::Connect(name)
+            | PortListAction::Disconnect(name)
+            | PortListAction::Reopen(name)
+            | PortListAction::Edit(name)
+            | PortListAction::Save(name) => name,
+        }
+    }
+}
+
 /// Popup showing list of available COM ports.
 ///
 /// Stateless regarding port data - receives it during render/handle_key
@@ -53,9 +88,17 @@ impl PortListPopup {
 
     /// Renders the port list.
     ///
-    /// Shows each port with a connection indicator (●), name, and baud rate.
+    /// Shows each port with a connection indicator (●), name, baud rate,
+    /// framing (e.g. `8N1`), flow control and connection state. `states`
+    /// is parallel to `ports`.
     /// Current selection is highlighted with a dark background.
-    pub fn render(&mut self, frame: &mut Frame, ports: &[(Arc<str>, Arc<PortConfig>)]) {
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        ports: &[(Arc<str>, Arc<PortConfig>)],
+        states: &[ConnectionState],
+        modem: &[Option<ModemLines>],
+    ) {
         if !self.visible {
             return;
         }
@@ -63,14 +106,36 @@ impl PortListPopup {
         let area = self.popup.area(frame.area());
         self.popup.clear(frame, area);
 
-        // Build list items: "● port_name  baud_rate"
+        // Build list items: "● port_name  baud_rate  8N1  flow  state  ● CTS ● DSR ● RI ● CD"
         let items: Vec<ListItem> = ports
             .iter()
-            .map(|(name, info)| {
-                let line = Line::from(vec![
-                    Span::styled("● ", Style::default().fg(Color::Green)),
-                    Span::raw(format!("{}  {}", name, info.baud_rate)),
+            .zip(states)
+            .zip(modem)
+            .map(|(((name, info), state), modem)| {
+                let state_color = match state {
+                    ConnectionState::Connected => Color::Green,
+                    ConnectionState::Reconnecting { .. } => Color::Yellow,
+                    ConnectionState::Disconnected => Color::Red,
+                    ConnectionState::Closed => Color::DarkGray,
+                };
+                let mut line = Line::from(vec![
+                    Span::styled("● ", Style::default().fg(state_color)),
+                    Span::raw(format!(
+                        "{}  {}  {}  {}  ",
+                        name,
+                        info.baud_rate,
+                        info.framing(),
+                        info.flow_control.as_str()
+                    )),
+                    Span::styled(state.to_string(), Style::default().fg(state_color)),
                 ]);
+                if let Some(modem) = modem {
+                    for (name, on) in modem.levels() {
+                        let color = if on { Color::Green } else { Color::DarkGray };
+                        line.push_span(Span::styled("  ● ", Style::default().fg(color)));
+                        line.push_span(Span::raw(name));
+                    }
+                }
                 ListItem::new(line)
             })
             .collect();
@@ -79,6 +144,9 @@ impl PortListPopup {
             .block(
                 Block::default()
                     .title(" Ports ")
+                    .title_bottom(
+                        " Enter toggle  c/d connect/disconnect  r reopen  e edit  s save ",
+                    )
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(Color::White)),
             )
@@ -92,14 +160,39 @@ impl PortListPopup {
     /// - `Esc` -> Close popup
     /// - `Up/k` -> Select previous
     /// - `Down/j` -> Select next
-    /// - `Enter` -> Select current port
-    pub fn handle_key(&mut self, key: KeyEvent, ports: &[(Arc<str>, Arc<PortConfig>)]) {
+    /// - `Enter/Space` -> Connect or disconnect the highlighted port
+    /// - `c` -> Connect
+    /// - `d` -> Disconnect
+    /// - `r` -> Reopen
+    /// - `e` -> Edit settings
+    /// - `s` -> Save settings to config
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        ports: &[(Arc<str>, Arc<PortConfig>)],
+    ) -> Option<PortListAction> {
         match key.code {
             KeyCode::Esc => self.visible = false,
             KeyCode::Up | KeyCode::Char('k') => select_prev(&mut self.list_state, ports.len()),
             KeyCode::Down | KeyCode::Char('j') => select_next(&mut self.list_state, ports.len()),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                return self.selected(ports).map(PortListAction::Toggle);
+            }
+            KeyCode::Char('c') => return self.selected(ports).map(PortListAction::Connect),
+            KeyCode::Char('d') => return self.selected(ports).map(PortListAction::Disconnect),
+            KeyCode::Char('r') => return self.selected(ports).map(PortListAction::Reopen),
+            KeyCode::Char('e') => return self.selected(ports).map(PortListAction::Edit),
+            KeyCode::Char('s') => return self.selected(ports).map(PortListAction::Save),
             _ => {}
         }
+        None
     }
 
+    /// Returns the name of the highlighted port.
+    fn selected(&self, ports: &[(Arc<str>, Arc<PortConfig>)]) -> Option<Arc<str>> {
+        self.list_state
+            .selected()
+            .and_then(|i| ports.get(i))
+            .map(|(name, _)| name.clone())
+    }
 }
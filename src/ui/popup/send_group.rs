@@ -67,6 +67,23 @@ impl SendGroupPopup {
         }
     }
 
+    /// Selects a single port (e.g. one that was just added).
+    pub fn select(&mut self, name: Arc<str>) {
+        self.selected.insert(name);
+    }
+
+    /// Deselects a port (e.g. one that was removed).
+    pub fn deselect(&mut self, name: &str) {
+        self.selected.remove(name);
+    }
+
+    /// Carries the selection state over to a renamed port.
+    pub fn rename(&mut self, old: &str, new: Arc<str>) {
+        if self.selected.remove(old) {
+            self.selected.insert(new);
+        }
+    }
+
     /// Renders the checkbox list of ports.
     ///
     /// Each item shows: `[x] port_name  baud_rate` or `[ ] port_name  baud_rate`
@@ -126,13 +143,11 @@ impl SendGroupPopup {
 
     /// Toggles the selected state of the currently highlighted port.
     fn toggle_selected(&mut self, ports: &[(Arc<str>, Arc<PortConfig>)]) {
-        if let Some(i) = self.list_state.selected() {
-            if let Some((name, _)) = ports.get(i) {
-                if !self.selected.remove(name) {
-                    self.selected.insert(name.clone());
-                }
-            }
+        if let Some(i) = self.list_state.selected()
+            && let Some((name, _)) = ports.get(i)
+            && !self.selected.remove(name)
+        {
+            self.selected.insert(name.clone());
         }
     }
-
 }
@@ -0,0 +1,185 @@
+//! Stats popup showing each port's throughput and error counters.
+//!
+//! Per port: RX and TX totals, the rate of the last second and the peak,
+//! a sparkline of the bytes per second over the last minute, and the read
+//! errors, reconnects and dropped sends. Scrolls by port with j/k.
+
+use std::sync::Arc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Sparkline},
+};
+
+use crate::serial::stats::{PortStats, Traffic, format_bytes};
+
+use super::Popup;
+
+/// Rows per port: name, RX line, RX sparkline (2), TX line, TX sparkline
+/// (2), errors line
+const PORT_HEIGHT: u16 = 8;
+
+/// Popup with a section of stats per port.
+pub struct StatsPopup {
+    /// Helper for centered positioning
+    popup: Popup,
+    /// Index of the first port shown
+    scroll: usize,
+    /// Whether the popup is currently shown
+    pub visible: bool,
+}
+
+impl StatsPopup {
+    /// Creates a new hidden stats popup.
+    ///
+    /// Uses 80% width, 80% height of the screen.
+    pub fn new() -> Self {
+        Self {
+            popup: Popup::new(80, 80),
+            scroll: 0,
+            visible: false,
+        }
+    }
+
+    /// Toggles visibility, scrolling back to the first port on open.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        if self.visible {
+            self.scroll = 0;
+        }
+    }
+
+    /// Renders as many port sections as fit, from the scroll position.
+    pub fn render(&mut self, frame: &mut Frame, stats: &[(Arc<str>, PortStats)]) {
+        if !self.visible {
+            return;
+        }
+
+        let area = self.popup.area(frame.area());
+        self.popup.clear(frame, area);
+
+        let block = Block::default()
+            .title(" Stats ")
+            .title_bottom(" j/k scroll  Esc close ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        if stats.is_empty() {
+            let empty = Span::styled("No ports", Style::default().fg(Color::DarkGray));
+            frame.render_widget(Paragraph::new(empty), inner);
+            return;
+        }
+
+        self.scroll = self.scroll.min(stats.len() - 1);
+        let fits = (inner.height / PORT_HEIGHT).max(1) as usize;
+        let shown = &stats[self.scroll..stats.len().min(self.scroll + fits)];
+        let areas =
+            Layout::vertical(vec![Constraint::Length(PORT_HEIGHT); shown.len()]).split(inner);
+        for ((name, port), area) in shown.iter().zip(areas.iter()) {
+            render_port(frame, *area, name, port);
+        }
+    }
+
+    /// Handles key input when this popup is visible.
+    ///
+    /// - `Esc` -> Close popup
+    /// - `Up/k` -> Scroll up one port
+    /// - `Down/j` -> Scroll down one port
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.visible = false,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            // Clamped to the port count on render
+            KeyCode::Down | KeyCode::Char('j') => self.scroll += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Renders one port's section.
+fn render_port(frame: &mut Frame, area: Rect, name: &str, stats: &PortStats) {
+    let [title, rx, rx_spark, tx, tx_spark, errors] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Length(2),
+        Constraint::Length(1),
+        Constraint::Length(2),
+        Constraint::Length(1),
+    ])
+    .areas(area);
+
+    let name_style = Style::default().fg(Color::Yellow);
+    frame.render_widget(Paragraph::new(Span::styled(name, name_style)), title);
+
+    for (label, traffic, line_area, spark_area, color) in [
+        ("RX", &stats.rx, rx, rx_spark, Color::Green),
+        ("TX", &stats.tx, tx, tx_spark, Color::Cyan),
+    ] {
+        frame.render_widget(Paragraph::new(traffic_line(label, traffic)), line_area);
+
+        // Newest second on the right edge
+        let width = spark_area.width as usize;
+        let skip = traffic.history.len().saturating_sub(width);
+        let bytes: Vec<u64> = traffic.history.iter().skip(skip).map(|r| r.bytes).collect();
+        let sparkline = Sparkline::default()
+            .data(bytes)
+            .style(Style::default().fg(color));
+        frame.render_widget(sparkline, spark_area);
+    }
+
+    let error_style = |count: u64| {
+        let color = if count > 0 {
+            Color::Red
+        } else {
+            Color::DarkGray
+        };
+        Style::default().fg(color)
+    };
+    let errors_line = Line::from(vec![
+        Span::styled(
+            format!("  read errors {}", stats.read_errors),
+            error_style(stats.read_errors),
+        ),
+        Span::styled(
+            format!("  reconnects {}", stats.reconnects),
+            error_style(stats.reconnects),
+        ),
+        Span::styled(
+            format!("  dropped {}", stats.dropped),
+            error_style(stats.dropped),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(errors_line), errors);
+}
+
+/// Formats `  RX 1.5 KiB  40 lines   now 120 B/s 3 lines/s   peak ...`.
+fn traffic_line(label: &str, traffic: &Traffic) -> Line<'static> {
+    let rate = traffic.rate();
+    let label_style = Style::default().fg(Color::Cyan);
+    Line::from(vec![
+        Span::styled(format!("  {label} "), label_style),
+        Span::raw(format!(
+            "{}  {} lines",
+            format_bytes(traffic.bytes),
+            traffic.lines
+        )),
+        Span::styled("   now ", label_style),
+        Span::raw(format!(
+            "{}/s {} lines/s",
+            format_bytes(rate.bytes),
+            rate.lines
+        )),
+        Span::styled("   peak ", label_style),
+        Span::raw(format!(
+            "{}/s {} lines/s",
+            format_bytes(traffic.peak.bytes),
+            traffic.peak.lines
+        )),
+    ])
+}
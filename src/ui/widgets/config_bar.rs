@@ -1,6 +1,7 @@
 //! Top configuration bar widget.
 //!
-//! Displays keybinding hints for port operations: [p]orts and [a]dd.
+//! Displays keybinding hints for port operations: [p]orts, [a]dd, [m]acros
+//! and [s]tats.
 
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -17,6 +18,12 @@ use super::focused_block;
 pub enum ConfigAction {
     /// Open the port list popup
     OpenPorts,
+    /// Open the add-port wizard
+    AddPort,
+    /// Open the macro popup
+    OpenMacros,
+    /// Open the stats popup
+    OpenStats,
 }
 
 /// Top bar showing port control keybindings.
@@ -25,14 +32,27 @@ pub struct ConfigBar;
 impl ConfigBar {
     /// Renders the config bar with keybinding hints.
     ///
-    /// Shows `[p]orts  [a]dd` with highlighted key letters.
-    pub fn render(&self, frame: &mut Frame, area: Rect, focused: bool) {
+    /// Shows `[p]orts  [a]dd  [m]acros  [s]tats` with highlighted key letters,
+    /// followed by `script` (name and line of a running script).
+    pub fn render(&self, frame: &mut Frame, area: Rect, focused: bool, script: Option<&str>) {
         let block = focused_block(" Config ", focused);
 
-        let content = Line::from(vec![
+        let mut content = Line::from(vec![
             Span::styled("P", Style::default().fg(Color::Yellow)),
             Span::styled("orts ", Style::default().fg(Color::Cyan)),
+            Span::styled(" A", Style::default().fg(Color::Yellow)),
+            Span::styled("dd ", Style::default().fg(Color::Cyan)),
+            Span::styled(" M", Style::default().fg(Color::Yellow)),
+            Span::styled("acros ", Style::default().fg(Color::Cyan)),
+            Span::styled(" S", Style::default().fg(Color::Yellow)),
+            Span::styled("tats ", Style::default().fg(Color::Cyan)),
         ]);
+        if let Some(script) = script {
+            content.push_span(Span::styled(
+                format!("  ▶ {script}"),
+                Style::default().fg(Color::Green),
+            ));
+        }
 
         let paragraph = Paragraph::new(content).block(block);
         frame.render_widget(paragraph, area);
@@ -42,9 +62,14 @@ impl ConfigBar {
     ///
     /// - `p` -> Open ports list
     /// - `a` -> Add new port
+    /// - `m` -> Open macros
+    /// - `s` -> Open stats
     pub fn handle_key(&mut self, key: KeyEvent) -> Option<ConfigAction> {
         match key.code {
             KeyCode::Char('p') => Some(ConfigAction::OpenPorts),
+            KeyCode::Char('a') => Some(ConfigAction::AddPort),
+            KeyCode::Char('m') => Some(ConfigAction::OpenMacros),
+            KeyCode::Char('s') => Some(ConfigAction::OpenStats),
             _ => None,
         }
     }
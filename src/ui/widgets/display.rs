@@ -1,23 +1,50 @@
 //! Main display area for serial output.
 //!
-//! Uses a circular buffer (VecDeque) with 10,000 line limit.
+//! Uses a circular buffer (VecDeque) with 10,000 entry limit.
 //! Supports cursor-based scrolling with margin-based auto-scroll.
 //!
-//! Lines are stored as pre-rendered `Line<'static>` for efficiency.
-//! Cursor highlighting is applied at render time.
+//! In follow mode (like `tail -f`) the cursor stays on the newest row.
+//! Moving it up pauses following; `G` or `F` resumes. While paused, the
+//! cursor, view and visual selection are shifted as rows are evicted or
+//! grow, so they stay on the same text.
+//!
+//! Entries keep their raw bytes and timestamps; the rows rendered from them
+//! for the current view mode and line options are cached as
+//! `Line<'static>` and rebuilt when either changes. Cursor highlighting is
+//! applied at render time.
+//!
+//! Search (`/` forward, `?` backward) takes a smart-case regex and updates
+//! its matches while typing. Matches are kept as absolute row numbers, so
+//! they stay valid as rows are evicted from the front.
+//!
+//! Filters (`&`) hide whole entries: a hidden entry renders to no rows, so
+//! the cursor, search and eviction never see it.
 
-use std::collections::VecDeque;
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::Range,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use chrono::{DateTime, Local};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     Frame,
     layout::Rect,
     style::{Color, Modifier, Style},
-    text::Line,
+    text::{Line, Span},
     widgets::Paragraph,
 };
+use regex::{Regex, RegexBuilder};
 
-use super::focused_block;
+use crate::config::layout::TimestampMode;
+
+use super::{
+    entry::{Entry, LineOptions, ViewMode},
+    filter::Filter,
+    focused_block,
+};
 
 /// Mutually exclusive interaction modes for the display widget.
 enum DisplayMode {
@@ -28,19 +55,33 @@ enum DisplayMode {
         /// Anchor line index where the visual selection started.
         selection_start: usize,
     },
-    /// Search input mode (`/` prompt is active).
+    /// Search input mode (`/` or `?` prompt is active).
     Search {
-        /// In-progress query typed after `/`.
+        /// In-progress query typed after the prompt.
         search_query: String,
+        /// Absolute row the cursor was on when the prompt opened.
+        origin: usize,
+        /// Search restored if the prompt is cancelled.
+        previous: SearchState,
+        /// Follow mode was on when the prompt opened.
+        following: bool,
+    },
+    /// Filter input mode (`&` prompt is active).
+    Filter {
+        /// In-progress filter typed after `&`.
+        filter_query: String,
     },
 }
 
-/// Search results from the last executed query.
+/// The active search pattern and the rows it matches.
+#[derive(Default)]
 struct SearchState {
-    /// Cached line indices that match the last completed search.
-    matches: Vec<usize>,
-    /// Current index within `matches` for n/N navigation.
-    match_idx: usize,
+    /// Compiled query; `None` when no search is active.
+    regex: Option<Regex>,
+    /// Entered with `?`, so `n` moves up.
+    backward: bool,
+    /// Matching rows as absolute row numbers, ascending.
+    matches: VecDeque<usize>,
 }
 
 /// Actions the display widget can request.
@@ -56,14 +97,42 @@ pub enum DisplayAction {
 /// Uses a VecDeque as a circular buffer for efficient push/pop.
 /// Cursor-based scrolling with 25% margin triggers auto-scroll.
 pub struct Display {
+    /// Shown at the start of the title
+    name: String,
     /// Mode that display is currently in.
     mode: DisplayMode,
     /// Search result cache used by normal/visual mode.
     search: SearchState,
-    /// Circular buffer of pre-rendered display lines (max 10,000)
+    /// Circular buffer of raw entries (max 10,000), shared with other
+    /// displays showing the same data
+    entries: VecDeque<Arc<Entry>>,
+    /// Number of rows each entry renders to, parallel to `entries`
+    row_counts: VecDeque<usize>,
+    /// Rows rendered from `entries` for `view_mode`; the cursor indexes these
     lines: VecDeque<Line<'static>>,
+    /// Entries dropped from the front so far, so `push_entry` ids stay valid
+    dropped: usize,
+    /// Rows dropped from the front so far; absolute row = this + index
+    rows_dropped: usize,
+    /// How data entries are rendered
+    view_mode: ViewMode,
+    /// Line format, timestamp mode and port tag
+    options: LineOptions,
+    /// Timestamp of the newest entry, for the delta of the next one
+    last_timestamp: Option<DateTime<Local>>,
+    /// Timestamp of each port's newest entry, for per-port deltas
+    port_timestamps: HashMap<Arc<str>, DateTime<Local>>,
+    /// Stacked filters; entries failing any of them are not rendered
+    filters: Vec<Filter>,
     /// Current cursor position (absolute index in buffer)
     cursor: usize,
+    /// Follow mode is off: new data no longer moves the cursor to the
+    /// bottom (until `G` / `F`)
+    paused: bool,
+    /// Entries shown since follow mode was paused
+    new_lines: usize,
+    /// Border is drawn in red until then (trigger alerts)
+    flash_until: Option<Instant>,
     /// First visible line index
     view_start: usize,
     /// Tracks if 'g' was pressed (for gg sequence)
@@ -73,50 +142,343 @@ pub struct Display {
 }
 
 impl Display {
-    /// Maximum lines to keep in buffer
-    const MAX_LINES: usize = 10_000;
+    /// Maximum entries to keep in buffer
+    const MAX_ENTRIES: usize = 10_000;
     /// Scroll margin as fraction of visible height (25%)
     const SCROLL_MARGIN: f32 = 0.25;
 
     /// Creates a new empty display.
     pub fn new() -> Self {
         Self {
+            name: "Display".into(),
             mode: DisplayMode::Normal,
-            search: SearchState {
-                matches: Vec::new(),
-                match_idx: 0,
-            },
+            search: SearchState::default(),
+            entries: VecDeque::new(),
+            row_counts: VecDeque::new(),
             lines: VecDeque::new(),
+            dropped: 0,
+            rows_dropped: 0,
+            view_mode: ViewMode::default(),
+            options: LineOptions::default(),
+            last_timestamp: None,
+            port_timestamps: HashMap::new(),
+            filters: Vec::new(),
             cursor: 0,
+            paused: false,
+            new_lines: 0,
+            flash_until: None,
             view_start: 0,
             pending_g: false,
             clipboard: None,
         }
     }
 
+    /// Changes the title.
+    pub fn rename(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    /// Creates a new empty display titled `name`.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::new()
+        }
+    }
+
+    /// Sets how lines are laid out.
+    pub fn with_options(mut self, options: LineOptions) -> Self {
+        self.options = options;
+        self
+    }
+
     /// Clears all lines and resets display state.
     pub fn clear(&mut self) {
+        self.dropped += self.entries.len();
+        self.rows_dropped += self.lines.len();
+        self.entries.clear();
+        self.row_counts.clear();
         self.lines.clear();
+        self.last_timestamp = None;
+        self.port_timestamps.clear();
         self.cursor = 0;
         self.view_start = 0;
+        self.new_lines = 0;
         self.pending_g = false;
         self.mode = DisplayMode::Normal;
         self.search.matches.clear();
-        self.search.match_idx = 0;
     }
 
-    /// Adds a pre-styled line to the buffer, removing oldest if at capacity.
-    /// Auto-scrolls to bottom by moving cursor to the new line.
-    pub fn push_line(&mut self, line: Line<'static>) {
-        if self.lines.len() >= Self::MAX_LINES {
-            self.lines.pop_front();
-            // Adjust view if it was pointing at removed line
-            self.view_start = self.view_start.saturating_sub(1);
+    /// Adds an entry to the buffer, removing the oldest if at capacity.
+    /// In follow mode, moves the cursor to the last new row; otherwise
+    /// counts the entry as a new line.
+    ///
+    /// Returns an id for `entry` and `replace_entry`.
+    pub fn push_entry(&mut self, entry: impl Into<Arc<Entry>>) -> usize {
+        let entry = entry.into();
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            self.entries.pop_front();
+            let rows = self.row_counts.pop_front().unwrap_or(0);
+            self.lines.drain(..rows);
+            self.dropped += 1;
+            self.rows_dropped += rows;
+            while self
+                .search
+                .matches
+                .front()
+                .is_some_and(|&row| row < self.rows_dropped)
+            {
+                self.search.matches.pop_front();
+            }
+            self.shift_rows(0, -(rows as isize));
         }
 
-        self.lines.push_back(line);
-        // Auto-scroll: move cursor to the last line
+        let previous = self.delta_base(self.last_timestamp, &self.port_timestamps, &entry.port);
+        let rows = self.render_entry(&entry, previous);
+        self.last_timestamp = Some(entry.timestamp);
+        self.port_timestamps
+            .insert(entry.port.clone(), entry.timestamp);
+        let start = self.lines.len();
+        self.row_counts.push_back(rows.len());
+        self.lines.extend(rows);
+        self.entries.push_back(entry);
+        let found = self.find_matches(start..self.lines.len());
+        self.search.matches.extend(found);
+        // Follow: move cursor to the last line
+        if !self.paused {
+            self.cursor = self.lines.len().saturating_sub(1);
+        } else if self.lines.len() > start {
+            self.new_lines += 1;
+        }
+        self.dropped + self.entries.len() - 1
+    }
+
+    /// Returns the entry added with `push_entry` as `id`, unless it has
+    /// since been dropped or cleared.
+    pub fn entry(&self, id: usize) -> Option<&Arc<Entry>> {
+        self.entries.get(id.checked_sub(self.dropped)?)
+    }
+
+    /// Replaces the entry added with `push_entry` as `id`, such as a
+    /// partial line that grew, and re-renders its rows. While paused, an
+    /// entry the filters hid until now counts as a new line.
+    ///
+    /// Returns false if the entry has since been dropped or cleared.
+    pub fn replace_entry(&mut self, id: usize, entry: impl Into<Arc<Entry>>) -> bool {
+        let Some(idx) = id
+            .checked_sub(self.dropped)
+            .filter(|&idx| idx < self.entries.len())
+        else {
+            return false;
+        };
+        self.entries[idx] = entry.into();
+
+        // The entry may start or stop passing the filters as it changes
+        let previous = self.previous_timestamp(idx);
+        let rows = self.render_entry(&self.entries[idx], previous);
+        let offset: usize = self.row_counts.range(..idx).sum();
+        let old_count = std::mem::replace(&mut self.row_counts[idx], rows.len());
+
+        // Swap the entry's rows in place, shifting any rows after it
+        let tail = self.lines.split_off(offset);
+        self.lines.extend(rows);
+        self.lines.extend(tail.into_iter().skip(old_count));
+
+        let new_count = self.row_counts[idx];
+        let added = new_count as isize - old_count as isize;
+
+        // Re-match the entry's rows and renumber the matches after it
+        let start = self.rows_dropped + offset;
+        let keep = self.search.matches.partition_point(|&row| row < start);
+        let after: Vec<usize> = self
+            .search
+            .matches
+            .drain(keep..)
+            .filter(|&row| row >= start + old_count)
+            .map(|row| row.saturating_add_signed(added))
+            .collect();
+        let found = self.find_matches(offset..offset + new_count);
+        self.search.matches.extend(found);
+        self.search.matches.extend(after);
+
+        self.shift_rows(offset + old_count, added);
+        if idx == self.entries.len() - 1 && !self.paused {
+            self.cursor = self.lines.len().saturating_sub(1);
+        } else if self.paused && old_count == 0 && new_count > 0 {
+            // Hidden when pushed, so not counted yet
+            self.new_lines += 1;
+        }
+        true
+    }
+
+    /// Rows at index `at` and later moved by `delta`: keeps the cursor, view
+    /// and selection on the same rows, or on the first row left if theirs
+    /// were removed.
+    fn shift_rows(&mut self, at: usize, delta: isize) {
+        let shift = |row: &mut usize| {
+            if *row >= at {
+                *row = row.saturating_add_signed(delta);
+            }
+        };
+        shift(&mut self.cursor);
+        shift(&mut self.view_start);
+        if let DisplayMode::Visual { selection_start } = &mut self.mode {
+            shift(selection_start);
+        }
+    }
+
+    /// Leaves follow mode, so new data no longer moves the cursor.
+    pub fn pause(&mut self) {
+        if !self.paused {
+            self.paused = true;
+            self.new_lines = 0;
+        }
+    }
+
+    /// Enters follow mode, moving the cursor to the newest row.
+    pub fn follow(&mut self, height: usize) {
+        self.paused = false;
+        self.new_lines = 0;
         self.cursor = self.lines.len().saturating_sub(1);
+        self.adjust_scroll(height);
+    }
+
+    /// Moves the cursor to `row`, pausing follow mode unless that is the
+    /// newest row.
+    fn move_cursor(&mut self, row: usize, height: usize) {
+        self.cursor = row.min(self.lines.len().saturating_sub(1));
+        if self.cursor + 1 < self.lines.len() {
+            self.pause();
+        }
+        self.adjust_scroll(height);
+    }
+
+    /// Flashes the border red for a moment.
+    pub fn flash(&mut self) {
+        const FLASH: Duration = Duration::from_millis(1500);
+        self.flash_until = Some(Instant::now() + FLASH);
+    }
+
+    /// Switches to the next view mode and re-renders the scrollback.
+    pub fn cycle_view_mode(&mut self) {
+        self.view_mode = self.view_mode.next();
+        self.rerender();
+    }
+
+    /// Switches to the next timestamp mode and re-renders the scrollback.
+    pub fn cycle_timestamp_mode(&mut self) {
+        self.options.timestamp = self.options.timestamp.next();
+        self.rerender();
+    }
+
+    /// Adds a filter to the stack and re-renders the scrollback.
+    pub fn add_filter(&mut self, filter: Filter) {
+        self.filters.push(filter);
+        self.rerender();
+    }
+
+    /// Removes all filters and re-renders the scrollback.
+    pub fn clear_filters(&mut self) {
+        if !self.filters.is_empty() {
+            self.filters.clear();
+            self.rerender();
+        }
+    }
+
+    /// Renders `entry` for the current view mode, or to no rows if it is
+    /// filtered out. `previous` is the timestamp a delta counts from.
+    fn render_entry(&self, entry: &Entry, previous: Option<DateTime<Local>>) -> Vec<Line<'static>> {
+        if self.filters.iter().all(|filter| filter.accepts(entry)) {
+            entry.render(self.view_mode, &self.options, previous)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Picks the timestamp a delta timestamp of an entry from `port`
+    /// counts from: the newest one before it (`last`), or the port's
+    /// (`ports`). Hidden entries count too.
+    fn delta_base(
+        &self,
+        last: Option<DateTime<Local>>,
+        ports: &HashMap<Arc<str>, DateTime<Local>>,
+        port: &str,
+    ) -> Option<DateTime<Local>> {
+        match self.options.timestamp {
+            TimestampMode::Delta => last,
+            TimestampMode::DeltaPort => ports.get(port).copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns the timestamp the delta of the entry at index `idx` counts
+    /// from. Looks back through the buffer, so it is only used for single
+    /// entries; `push_entry` and `rerender` keep track as they go.
+    fn previous_timestamp(&self, idx: usize) -> Option<DateTime<Local>> {
+        let port = &self.entries[idx].port;
+        let mut before = self.entries.range(..idx).rev();
+        match self.options.timestamp {
+            TimestampMode::Delta => before.next(),
+            TimestampMode::DeltaPort => before.find(|entry| entry.port == *port),
+            _ => None,
+        }
+        .map(|entry| entry.timestamp)
+    }
+
+    /// Re-renders every entry after the view mode, timestamp mode or
+    /// filters changed.
+    ///
+    /// The cursor stays on the same row of the same entry, or moves to the
+    /// next shown entry if that one is now hidden. Search matches are
+    /// recomputed and visual selection is reset since row numbers change.
+    fn rerender(&mut self) {
+        // Entry under the cursor, and the cursor's row within it
+        let (mut cursor_entry, mut row_in_entry) = (0, 0);
+        let mut entry_start = 0;
+        for (idx, &rows) in self.row_counts.iter().enumerate() {
+            if self.cursor < entry_start + rows {
+                (cursor_entry, row_in_entry) = (idx, self.cursor - entry_start);
+                break;
+            }
+            entry_start += rows;
+        }
+        let at_bottom = !self.paused || self.lines.is_empty();
+
+        let mut last = None;
+        let mut ports = HashMap::new();
+        let rendered: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let rows = self.render_entry(entry, self.delta_base(last, &ports, &entry.port));
+                last = Some(entry.timestamp);
+                ports.insert(entry.port.clone(), entry.timestamp);
+                rows
+            })
+            .collect();
+        self.lines.clear();
+        self.row_counts.clear();
+        for rows in rendered {
+            self.row_counts.push_back(rows.len());
+            self.lines.extend(rows);
+        }
+
+        let last = self.lines.len().saturating_sub(1);
+        self.cursor = if at_bottom {
+            last
+        } else {
+            let offset: usize = self.row_counts.range(..cursor_entry).sum();
+            let rows = self.row_counts.get(cursor_entry).copied().unwrap_or(0);
+            (offset + row_in_entry.min(rows.saturating_sub(1))).min(last)
+        };
+        self.mode = DisplayMode::Normal;
+        self.search.matches = self.find_matches(0..self.lines.len()).into();
+    }
+
+    /// Returns true in visual, search or filter mode, where `Esc` leaves
+    /// the mode.
+    pub fn is_modal(&self) -> bool {
+        !matches!(self.mode, DisplayMode::Normal)
     }
 
     /// Toggles visual selection mode.
@@ -125,6 +487,8 @@ impl Display {
     pub fn toggle_visual(&mut self) {
         match self.mode {
             DisplayMode::Normal => {
+                // Keep new data from dragging the selection along
+                self.pause();
                 self.mode = DisplayMode::Visual {
                     selection_start: self.cursor,
                 };
@@ -132,7 +496,7 @@ impl Display {
             DisplayMode::Visual { .. } => {
                 self.mode = DisplayMode::Normal;
             }
-            DisplayMode::Search { .. } => {}
+            DisplayMode::Search { .. } | DisplayMode::Filter { .. } => {}
         }
     }
 
@@ -164,13 +528,7 @@ impl Display {
             .iter()
             .skip(start)
             .take(end - start + 1)
-            .map(|line| {
-                // Extract raw text from Line's spans
-                line.spans
-                    .iter()
-                    .map(|span| span.content.as_ref())
-                    .collect::<String>()
-            })
+            .map(line_text)
             .collect::<Vec<_>>()
             .join("\n")
     }
@@ -199,89 +557,163 @@ impl Display {
         Ok(num_lines)
     }
 
-    /// Exits search input mode and executes the search.
-    pub fn finish_search(&mut self, height: usize) {
-        let DisplayMode::Search { search_query } =
-            std::mem::replace(&mut self.mode, DisplayMode::Normal)
-        else {
-            return;
+    /// Opens the search prompt; `backward` searches upwards like `?`.
+    ///
+    /// The current search is set aside and restored if the prompt is
+    /// cancelled.
+    pub fn start_search(&mut self, backward: bool) {
+        let mut previous = std::mem::take(&mut self.search);
+        previous.matches.clear();
+        self.search.backward = backward;
+        self.mode = DisplayMode::Search {
+            search_query: String::new(),
+            origin: self.rows_dropped + self.cursor,
+            previous,
+            following: !self.paused,
         };
+        self.pending_g = false;
+    }
 
-        self.search.matches.clear();
-        self.search.match_idx = 0;
-
-        if !search_query.is_empty() {
-            let query_lower = search_query.to_lowercase();
-
-            for (idx, line) in self.lines.iter().enumerate() {
-                let text: String = line
-                    .spans
-                    .iter()
-                    .map(|span| span.content.as_ref())
-                    .collect();
-
-                if text.to_lowercase().contains(&query_lower) {
-                    self.search.matches.push(idx);
-                }
-            }
+    /// Exits search input mode, keeping the search typed so far.
+    ///
+    /// An empty query cancels instead. Returns the error if the query is
+    /// not a valid regex, in which case the previous search is restored.
+    pub fn finish_search(&mut self, height: usize) -> Option<regex::Error> {
+        let DisplayMode::Search { search_query, .. } = &self.mode else {
+            return None;
+        };
+        if search_query.is_empty() {
+            self.cancel_search(height);
+            return None;
         }
-
-        if let Some(&first) = self.search.matches.first() {
-            self.cursor = first;
-            self.adjust_scroll(height);
+        if let Err(e) = compile_query(search_query) {
+            self.cancel_search(height);
+            return Some(e);
         }
+        self.mode = DisplayMode::Normal;
+        None
     }
 
-    /// Cancels search mode without executing.
-    pub fn cancel_search(&mut self) {
-        if matches!(self.mode, DisplayMode::Search { .. }) {
-            self.mode = DisplayMode::Normal;
+    /// Leaves search mode, restoring the previous search, cursor and
+    /// follow mode.
+    pub fn cancel_search(&mut self, height: usize) {
+        let DisplayMode::Search {
+            origin,
+            previous,
+            following,
+            ..
+        } = std::mem::replace(&mut self.mode, DisplayMode::Normal)
+        else {
+            return;
+        };
+        self.search = previous;
+        self.search.matches = self.find_matches(0..self.lines.len()).into();
+        if following {
+            self.follow(height);
+        } else {
+            self.move_cursor(self.row_index(origin), height);
         }
     }
 
     /// Adds a character to the search query.
-    pub fn search_push(&mut self, c: char) {
-        if let DisplayMode::Search { search_query } = &mut self.mode {
+    pub fn search_push(&mut self, c: char, height: usize) {
+        if let DisplayMode::Search { search_query, .. } = &mut self.mode {
             search_query.push(c);
+            self.update_search(height);
         }
     }
 
-    /// Removes the last character from the search query.
-    pub fn search_pop(&mut self) {
-        if let DisplayMode::Search { search_query } = &mut self.mode {
-            search_query.pop();
+    /// Removes the last character from the search query, cancelling the
+    /// search when it is already empty.
+    pub fn search_pop(&mut self, height: usize) {
+        if let DisplayMode::Search { search_query, .. } = &mut self.mode {
+            if search_query.pop().is_none() {
+                self.cancel_search(height);
+            } else {
+                self.update_search(height);
+            }
         }
     }
 
-    /// Jumps to the next search match.
-    pub fn next_match(&mut self, height: usize) {
-        if self.search.matches.is_empty() {
+    /// Re-runs the in-progress query and moves the cursor to the first
+    /// match from where the search started.
+    fn update_search(&mut self, height: usize) {
+        let DisplayMode::Search {
+            search_query,
+            origin,
+            ..
+        } = &self.mode
+        else {
             return;
-        }
+        };
+        let origin = *origin;
 
-        self.search.match_idx = (self.search.match_idx + 1) % self.search.matches.len();
-        self.cursor = self.search.matches[self.search.match_idx];
-        self.adjust_scroll(height);
+        // An invalid query (often half-typed) matches nothing
+        self.search.regex = Some(search_query)
+            .filter(|query| !query.is_empty())
+            .and_then(|query| compile_query(query).ok());
+        self.search.matches = self.find_matches(0..self.lines.len()).into();
+
+        let origin = self.row_index(origin);
+        let row = self
+            .find_match(origin, self.search.backward, true)
+            .unwrap_or(origin);
+        self.move_cursor(row, height);
     }
 
-    /// Jumps to the previous search match.
+    /// Jumps to the next search match in the search direction.
+    pub fn next_match(&mut self, height: usize) {
+        if let Some(row) = self.find_match(self.cursor, self.search.backward, false) {
+            self.move_cursor(row, height);
+        }
+    }
+
+    /// Jumps to the next search match against the search direction.
     pub fn prev_match(&mut self, height: usize) {
-        if self.search.matches.is_empty() {
-            return;
+        if let Some(row) = self.find_match(self.cursor, !self.search.backward, false) {
+            self.move_cursor(row, height);
         }
+    }
 
-        self.search.match_idx = if self.search.match_idx == 0 {
-            self.search.matches.len() - 1
+    /// Returns the index of the first match after (or before, if
+    /// `backward`) row `from`, wrapping around the ends.
+    ///
+    /// `inclusive` also accepts `from` itself.
+    fn find_match(&self, from: usize, backward: bool, inclusive: bool) -> Option<usize> {
+        let matches = &self.search.matches;
+        let from = self.rows_dropped + from;
+        let found = if backward {
+            let before = matches.partition_point(|&row| row < from + usize::from(inclusive));
+            before
+                .checked_sub(1)
+                .and_then(|i| matches.get(i))
+                .or(matches.back())
         } else {
-            self.search.match_idx - 1
+            let after = matches.partition_point(|&row| row < from + usize::from(!inclusive));
+            matches.get(after).or(matches.front())
         };
-        self.cursor = self.search.matches[self.search.match_idx];
-        self.adjust_scroll(height);
+        found.map(|&row| row - self.rows_dropped)
     }
 
-    /// Returns true if the given line index is a search match.
-    fn is_match(&self, idx: usize) -> bool {
-        self.search.matches.binary_search(&idx).is_ok()
+    /// Returns the absolute numbers of the rows in `range` that match the
+    /// active search.
+    fn find_matches(&self, range: Range<usize>) -> Vec<usize> {
+        let Some(regex) = &self.search.regex else {
+            return Vec::new();
+        };
+        self.lines
+            .range(range.clone())
+            .zip(range)
+            .filter(|(line, _)| regex.is_match(&line_text(line)))
+            .map(|(_, idx)| self.rows_dropped + idx)
+            .collect()
+    }
+
+    /// Converts an absolute row number to an index into `lines`, clamped to
+    /// the rows still in the buffer.
+    fn row_index(&self, row: usize) -> usize {
+        row.saturating_sub(self.rows_dropped)
+            .min(self.lines.len().saturating_sub(1))
     }
 
     /// Adjusts view_start to keep cursor within scroll margins.
@@ -323,24 +755,47 @@ impl Display {
     /// Renders the display with highlighted cursor, selection, and search matches.
     pub fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool) {
         // Update block title to show mode indicators
+        let mut name = self.name.clone();
+        if self.view_mode != ViewMode::Text {
+            name.push_str(&format!(" [{}]", self.view_mode.label()));
+        }
+        if self.options.timestamp != TimestampMode::default() {
+            name.push_str(&format!(" [{}]", self.options.timestamp.label()));
+        }
+        if !self.filters.is_empty() {
+            let filters: Vec<String> = self.filters.iter().map(Filter::to_string).collect();
+            name.push_str(&format!(" [&{}]", filters.join(" ")));
+        }
         let title = match self.mode {
-            DisplayMode::Search { .. } => " Display [SEARCH] ".to_string(),
-            DisplayMode::Visual { .. } => " Display [VISUAL] ".to_string(),
-            DisplayMode::Normal if !self.search.matches.is_empty() => {
-                format!(
-                    " Display [{}/{}] ",
-                    self.search.match_idx + 1,
-                    self.search.matches.len()
-                )
+            DisplayMode::Search { .. } => format!(" {name} [SEARCH] "),
+            DisplayMode::Filter { .. } => format!(" {name} [FILTER] "),
+            DisplayMode::Visual { .. } => format!(" {name} [VISUAL] "),
+            DisplayMode::Normal if self.search.regex.is_some() => {
+                // Matches up to and including the cursor row
+                let cursor = self.rows_dropped + self.cursor;
+                let current = self.search.matches.partition_point(|&row| row <= cursor);
+                format!(" {name} [{current}/{}] ", self.search.matches.len())
             }
-            DisplayMode::Normal => " Display ".to_string(),
+            DisplayMode::Normal => format!(" {name} "),
+        };
+        let title = match (self.paused, self.new_lines) {
+            (false, _) => title,
+            (true, 0) => format!("{title}[PAUSED] "),
+            (true, 1) => format!("{title}[PAUSED, 1 new line] "),
+            (true, n) => format!("{title}[PAUSED, {n} new lines] "),
         };
 
-        let block = focused_block(&title, focused);
+        let mut block = focused_block(&title, focused);
+        if self.flash_until.is_some_and(|until| Instant::now() < until) {
+            block = block.border_style(Style::default().fg(Color::Red));
+        }
         let inner = block.inner(area);
 
-        // Reserve one line for search input when in search mode
-        let content_height = if matches!(self.mode, DisplayMode::Search { .. }) {
+        // Reserve one line for the prompt in search and filter mode
+        let content_height = if matches!(
+            self.mode,
+            DisplayMode::Search { .. } | DisplayMode::Filter { .. }
+        ) {
             inner.height.saturating_sub(1) as usize
         } else {
             inner.height as usize
@@ -360,28 +815,44 @@ impl Display {
         // Pre-allocate Vec to avoid resizing during iteration
         let mut lines = Vec::with_capacity(content_height);
 
-        // Build visible lines, applying cursor/selection/match highlight
+        // Build visible lines, applying cursor/selection highlight and
+        // marking the matched text
         for (idx, line) in self.visible_lines(content_height) {
+            let line = match &self.search.regex {
+                Some(regex) => highlight_matches(line, regex, match_style),
+                None => line.clone(),
+            };
             let styled_line = if idx == self.cursor {
                 // Cursor line gets cursor style
-                line.clone().style(cursor_style)
+                line.style(cursor_style)
             } else if self.is_selected(idx) {
                 // Selected lines get selection style
-                line.clone().style(selection_style)
-            } else if self.is_match(idx) {
-                // Search match lines get match style
-                line.clone().style(match_style)
+                line.style(selection_style)
             } else {
                 // Normal lines
-                line.clone()
+                line
             };
             lines.push(styled_line);
         }
 
         // Add search input line when in search mode
-        if let DisplayMode::Search { search_query } = &self.mode {
+        if let DisplayMode::Search { search_query, .. } = &self.mode {
+            let prompt = if self.search.backward { '?' } else { '/' };
+            let mut spans = vec![Span::styled(
+                format!("{prompt}{search_query}"),
+                Style::default().fg(Color::Cyan),
+            )];
+            if !search_query.is_empty() && self.search.regex.is_none() {
+                spans.push(Span::styled(
+                    "  (invalid regex)",
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        if let DisplayMode::Filter { filter_query } = &self.mode {
             lines.push(Line::styled(
-                format!("/{search_query}"),
+                format!("&{filter_query}"),
                 Style::default().fg(Color::Cyan),
             ));
         }
@@ -397,29 +868,68 @@ impl Display {
     /// - `Ctrl+u` -> Half page up
     /// - `Ctrl+d` -> Half page down
     /// - `gg` -> Go to top
-    /// - `G` -> Go to bottom
+    /// - `G` / `F` -> Go to bottom and follow new data
     /// - `v` / `V` -> Toggle visual selection mode
     /// - `y` -> Yank (copy) selected lines to clipboard
-    /// - `/` -> Start search mode
-    /// - `n` -> Next search match
-    /// - `N` -> Previous search match
-    /// - `Esc` -> Exit visual/search mode (if active)
-    /// - `Enter` -> Move focus to input bar (or execute search in search mode)
+    /// - `x` -> Cycle view mode (text / hex / escaped)
+    /// - `t` -> Cycle timestamp mode (time / micros / date / relative /
+    ///   delta / delta per port / off)
+    /// - `/` / `?` -> Start forward / backward regex search
+    /// - `n` -> Next search match in the search direction
+    /// - `N` -> Next search match in the opposite direction
+    /// - `&` -> Add a filter (`&text`, `&!text`, `&@port,port`; empty clears)
+    /// - `Esc` -> Exit visual mode, or cancel search / filter input (if active)
+    /// - `Enter` -> Move focus to input bar (or keep the search / apply the
+    ///   filter)
     pub fn handle_key(&mut self, key: KeyEvent, height: usize) -> Option<DisplayAction> {
+        // Handle filter mode input
+        if let DisplayMode::Filter { filter_query } = &mut self.mode {
+            match key.code {
+                KeyCode::Esc => self.mode = DisplayMode::Normal,
+                KeyCode::Enter => {
+                    let query = std::mem::take(filter_query);
+                    self.mode = DisplayMode::Normal;
+                    if query.is_empty() {
+                        self.clear_filters();
+                    } else {
+                        match Filter::parse(&query) {
+                            Ok(filter) => self.add_filter(filter),
+                            Err(e) => {
+                                return Some(DisplayAction::Notify(format!("Invalid filter: {e}")));
+                            }
+                        }
+                    }
+                    self.adjust_scroll(height);
+                }
+                KeyCode::Backspace => {
+                    if filter_query.is_empty() {
+                        self.mode = DisplayMode::Normal;
+                    } else {
+                        filter_query.pop();
+                    }
+                }
+                KeyCode::Char(c) => filter_query.push(c),
+                _ => {}
+            }
+            return None;
+        }
+
         // Handle search mode input
         if matches!(self.mode, DisplayMode::Search { .. }) {
             match key.code {
                 KeyCode::Esc => {
-                    self.cancel_search();
+                    self.cancel_search(height);
                 }
                 KeyCode::Enter => {
-                    self.finish_search(height);
+                    if let Some(e) = self.finish_search(height) {
+                        return Some(DisplayAction::Notify(format!("Invalid search: {e}")));
+                    }
                 }
                 KeyCode::Backspace => {
-                    self.search_pop();
+                    self.search_pop(height);
                 }
                 KeyCode::Char(c) => {
-                    self.search_push(c);
+                    self.search_push(c, height);
                 }
                 _ => {}
             }
@@ -430,39 +940,55 @@ impl Display {
             // Go half way up the page
             (KeyModifiers::CONTROL, KeyCode::Char('u')) => {
                 let half = height / 2;
-                self.cursor = self.cursor.saturating_sub(half);
-                self.adjust_scroll(height);
+                self.move_cursor(self.cursor.saturating_sub(half), height);
                 None
             }
             // Go half way down the page
             (KeyModifiers::CONTROL, KeyCode::Char('d')) => {
                 let half = height / 2;
-                let max_cursor = self.lines.len().saturating_sub(1);
-                self.cursor = (self.cursor + half).min(max_cursor);
-                self.adjust_scroll(height);
+                self.move_cursor(self.cursor + half, height);
                 None
             }
             (_, KeyCode::Char('g')) => {
-                if self.pending_g == true {
+                if self.pending_g {
                     self.pending_g = false;
-                    self.cursor = 0;
-                    self.adjust_scroll(height);
+                    self.move_cursor(0, height);
                 } else {
                     // First 'g' - wait for second
                     self.pending_g = true;
                 }
                 None
             }
-            // Go to bottom
-            (KeyModifiers::SHIFT, KeyCode::Char('G')) => {
-                self.cursor = self.lines.len().saturating_sub(1);
+            // Go to bottom and follow new data again
+            (KeyModifiers::SHIFT, KeyCode::Char('G' | 'F')) => {
+                self.follow(height);
+                None
+            }
+            // Cycle text / hex / escaped rendering
+            (_, KeyCode::Char('x')) => {
+                self.cycle_view_mode();
+                self.adjust_scroll(height);
+                None
+            }
+            // Cycle the timestamp mode
+            (_, KeyCode::Char('t')) => {
+                self.cycle_timestamp_mode();
                 self.adjust_scroll(height);
                 None
             }
             // Start search mode
             (_, KeyCode::Char('/')) => {
-                self.mode = DisplayMode::Search {
-                    search_query: String::new(),
+                self.start_search(false);
+                None
+            }
+            (_, KeyCode::Char('?')) => {
+                self.start_search(true);
+                None
+            }
+            // Start filter mode
+            (_, KeyCode::Char('&')) => {
+                self.mode = DisplayMode::Filter {
+                    filter_query: String::new(),
                 };
                 self.pending_g = false;
                 None
@@ -496,15 +1022,13 @@ impl Display {
             }
             (_, KeyCode::Char('k') | KeyCode::Up) => {
                 if self.cursor > 0 {
-                    self.cursor -= 1;
-                    self.adjust_scroll(height);
+                    self.move_cursor(self.cursor - 1, height);
                 }
                 None
             }
             (_, KeyCode::Char('j') | KeyCode::Down) => {
                 if self.cursor < self.lines.len().saturating_sub(1) {
-                    self.cursor += 1;
-                    self.adjust_scroll(height);
+                    self.move_cursor(self.cursor + 1, height);
                 }
                 None
             }
@@ -513,3 +1037,265 @@ impl Display {
         }
     }
 }
+
+/// Returns the text of a rendered row.
+fn line_text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Compiles a search query, ignoring case unless it contains an
+/// uppercase letter (smart-case).
+pub(super) fn compile_query(query: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(query)
+        .case_insensitive(!query.chars().any(char::is_uppercase))
+        .build()
+}
+
+/// Returns a copy of `line` with the text matched by `regex` in `style`.
+///
+/// Spans are split at the match boundaries so the rest of the row keeps
+/// its own styling.
+fn highlight_matches(line: &Line<'static>, regex: &Regex, style: Style) -> Line<'static> {
+    let text = line_text(line);
+    let ranges: Vec<Range<usize>> = regex
+        .find_iter(&text)
+        .filter(|m| !m.is_empty())
+        .map(|m| m.range())
+        .collect();
+    if ranges.is_empty() {
+        return line.clone();
+    }
+
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for span in &line.spans {
+        let content = span.content.as_ref();
+        let end = start + content.len();
+        // Byte offset in the row up to which this span has been copied
+        let mut done = start;
+        for range in ranges.iter().filter(|r| r.start < end && r.end > start) {
+            let (from, to) = (range.start.max(start), range.end.min(end));
+            if from > done {
+                spans.push(Span::styled(
+                    content[done - start..from - start].to_string(),
+                    span.style,
+                ));
+            }
+            spans.push(Span::styled(
+                content[from - start..to - start].to_string(),
+                span.style.patch(style),
+            ));
+            done = to;
+        }
+        if done < end {
+            spans.push(Span::styled(
+                content[done - start..].to_string(),
+                span.style,
+            ));
+        }
+        start = end;
+    }
+
+    let mut highlighted = line.clone();
+    highlighted.spans = spans;
+    highlighted
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Local;
+    use ratatui::style::Color;
+
+    use super::*;
+
+    #[test]
+    fn test_replace_entry() {
+        let mut display = Display::new();
+        let id = display.push_entry(Entry::data("dut", b"login:"));
+        assert!(display.replace_entry(id, Entry::data("dut", b"login: root")));
+        assert!(display.get_selected_text().ends_with("[dut] login: root"));
+
+        // Ids stay valid while older entries are dropped, until their own goes
+        for _ in 0..Display::MAX_ENTRIES - 1 {
+            display.push_entry(Entry::data("dut", b"x"));
+        }
+        let last = display.push_entry(Entry::data("dut", b"y"));
+        assert!(display.entry(id).is_none());
+        assert!(!display.replace_entry(id, Entry::data("dut", b"!")));
+        assert!(display.replace_entry(last, Entry::data("dut", b"yz")));
+        assert!(display.get_selected_text().ends_with("[dut] yz"));
+
+        display.clear();
+        assert!(display.entry(last).is_none());
+        assert!(!display.replace_entry(last, Entry::data("dut", b"!")));
+    }
+
+    #[test]
+    fn test_cycle_view_mode() {
+        let mut display = Display::new();
+        let id = display.push_entry(Entry::data("dut", &[0xAA; 20]));
+        display.push_entry(Entry::data("dut", b"ok"));
+
+        display.cycle_view_mode();
+        // 20 bytes take two hex rows
+        assert_eq!(display.lines.len(), 3);
+        assert_eq!(display.cursor, 2);
+
+        // Growing an earlier entry shifts the rows after it
+        display.replace_entry(id, Entry::data("dut", &[0xAA; 36]));
+        assert_eq!(display.lines.len(), 4);
+        assert_eq!(display.cursor, 3);
+
+        display.cycle_view_mode();
+        assert!(display.get_selected_text().ends_with("[dut] ok"));
+    }
+
+    #[test]
+    fn test_timestamp_modes() {
+        let start = Local::now();
+        let mut display = Display::new().with_options(LineOptions {
+            timestamp: TimestampMode::DeltaPort,
+            ..Default::default()
+        });
+        for (ms, port) in [(0, "a"), (100, "b"), (250, "a"), (400, "b")] {
+            display.push_entry(Entry {
+                timestamp: start + chrono::TimeDelta::milliseconds(ms),
+                ..Entry::data(port, b"x")
+            });
+        }
+        assert_eq!(display.get_selected_text(), "[+0.300] [b] x");
+
+        // Cycling re-renders the scrollback from the raw entries
+        display.cycle_timestamp_mode();
+        assert_eq!(display.options.timestamp, TimestampMode::Off);
+        assert_eq!(display.get_selected_text(), "[b] x");
+        for _ in 0..5 {
+            display.cycle_timestamp_mode();
+        }
+        assert_eq!(display.options.timestamp, TimestampMode::Delta);
+        display.move_cursor(2, 10);
+        assert_eq!(display.get_selected_text(), "[+0.150] [a] x");
+    }
+
+    #[test]
+    fn test_follow() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let mut display = Display::new();
+        for _ in 0..Display::MAX_ENTRIES {
+            display.push_entry(Entry::data("dut", b"x"));
+        }
+        let id = display.push_entry(Entry::data("dut", b"first"));
+        display.push_entry(Entry::data("dut", b"second"));
+
+        // Scrolling up pauses; the selection stays on its rows as the
+        // oldest ones are evicted
+        display.handle_key(key('k'), 10);
+        display.handle_key(key('v'), 10);
+        display.handle_key(key('j'), 10);
+        display.push_entry(Entry::data("dut", b"third"));
+        display.replace_entry(id, Entry::data("dut", b"first!"));
+        let selected = display.get_selected_text();
+        assert!(selected.contains("[dut] first!\n"));
+        assert!(selected.ends_with("[dut] second"));
+        assert_eq!(display.new_lines, 1);
+
+        display.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), 10);
+        let shift_f = KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT);
+        display.handle_key(shift_f, 10);
+        assert!(!display.paused);
+        assert!(display.get_selected_text().ends_with("[dut] third"));
+
+        // A line that only passes the filters once complete is counted then
+        display.pause();
+        display.add_filter(Filter::parse("temp").unwrap());
+        let id = display.push_entry(Entry::data("dut", b"te"));
+        assert_eq!(display.new_lines, 0);
+        display.replace_entry(id, Entry::data("dut", b"temp 21"));
+        display.replace_entry(id, Entry::data("dut", b"temp 21\n"));
+        assert_eq!(display.new_lines, 1);
+    }
+
+    #[test]
+    fn test_filters() {
+        let mut display = Display::new();
+        for text in ["boot", "temp 20", "debug", "temp 21", "idle"] {
+            display.push_entry(Entry::data("dut", text.as_bytes()));
+        }
+        display.add_filter(Filter::parse("temp").unwrap());
+        assert_eq!(display.lines.len(), 2);
+
+        // New lines are filtered too, and stacked filters all apply
+        let id = display.push_entry(Entry::data("dut", b"temp"));
+        assert_eq!(display.lines.len(), 3);
+        display.add_filter(Filter::parse("!21").unwrap());
+        assert_eq!(display.lines.len(), 2);
+        display.move_cursor(0, 10);
+        display.replace_entry(id, Entry::data("dut", b"temp 21"));
+        assert_eq!(display.lines.len(), 1);
+        assert!(display.get_selected_text().ends_with("[dut] temp 20"));
+        display.clear_filters();
+        assert_eq!(display.lines.len(), 6);
+        assert_eq!(display.cursor, 1);
+        assert!(display.get_selected_text().ends_with("[dut] temp 20"));
+    }
+
+    #[test]
+    fn test_search() {
+        let mut display = Display::new();
+        for i in 0..Display::MAX_ENTRIES {
+            let text = if i % 2 == 0 { "Error 42" } else { "ok" };
+            display.push_entry(Entry::data("dut", text.as_bytes()));
+        }
+
+        // Matches update while typing; lowercase ignores case
+        display.start_search(true);
+        for c in "err.r".chars() {
+            display.search_push(c, 10);
+        }
+        assert_eq!(display.search.matches.len(), Display::MAX_ENTRIES / 2);
+        assert_eq!(display.cursor, Display::MAX_ENTRIES - 2);
+        assert!(display.finish_search(10).is_none());
+
+        // Evicting rows keeps the cursor and the remaining matches on
+        // their rows
+        display.push_entry(Entry::data("dut", b"ok"));
+        display.push_entry(Entry::data("dut", b"ERROR"));
+        assert_eq!(display.search.matches.len(), Display::MAX_ENTRIES / 2);
+        assert_eq!(display.cursor, Display::MAX_ENTRIES - 4);
+        assert_eq!(display.new_lines, 2);
+        // `?` search, so N goes down
+        display.prev_match(10);
+        assert_eq!(display.cursor, Display::MAX_ENTRIES - 1);
+        assert!(display.get_selected_text().ends_with("[dut] ERROR"));
+        display.prev_match(10);
+        // Wrapped to the oldest row left, which matches again
+        assert_eq!(display.cursor, 0);
+        assert!(display.get_selected_text().ends_with("[dut] Error 42"));
+
+        // An uppercase letter makes the search case-sensitive
+        display.start_search(false);
+        for c in "ERR".chars() {
+            display.search_push(c, 10);
+        }
+        assert_eq!(display.search.matches.len(), 1);
+        display.cancel_search(10);
+        assert_eq!(display.search.matches.len(), Display::MAX_ENTRIES / 2);
+
+        // Only the matched text is highlighted
+        let style = Style::default().bg(Color::Yellow);
+        let regex = compile_query("r+").unwrap();
+        let line = highlight_matches(&Line::from("error"), &regex, style);
+        let spans: Vec<_> = line
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style == style))
+            .collect();
+        assert_eq!(
+            spans,
+            [("e", false), ("rr", true), ("o", false), ("r", true)]
+        );
+    }
+}
@@ -0,0 +1,385 @@
+//! Raw display entries and how they are rendered in each view mode.
+//!
+//! The display keeps received bytes instead of pre-rendered text so the
+//! whole scrollback can be re-rendered when the view mode or timestamp
+//! mode changes.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Local, TimeDelta};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::{
+    config::layout::{Field, LineFormat, TimestampMode},
+    serial::Direction,
+};
+
+/// How received data is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ViewMode {
+    /// UTF-8 text (invalid bytes become replacement characters)
+    #[default]
+    Text,
+    /// `xxd`-style hex dump with an ASCII column
+    Hex,
+    /// Text with non-printable bytes escaped as `\xNN`
+    Escaped,
+}
+
+impl ViewMode {
+    /// Returns the mode after this one (Text -> Hex -> Escaped -> Text).
+    pub fn next(self) -> Self {
+        match self {
+            ViewMode::Text => ViewMode::Hex,
+            ViewMode::Hex => ViewMode::Escaped,
+            ViewMode::Escaped => ViewMode::Text,
+        }
+    }
+
+    /// Short name shown in the display title.
+    pub fn label(self) -> &'static str {
+        match self {
+            ViewMode::Text => "TEXT",
+            ViewMode::Hex => "HEX",
+            ViewMode::Escaped => "ESC",
+        }
+    }
+}
+
+/// How a display lays out the fields around an entry's data.
+#[derive(Clone)]
+pub struct LineOptions {
+    pub format: LineFormat,
+    pub timestamp: TimestampMode,
+    /// Show the `{port}` field
+    pub port_tag: bool,
+    /// Start of the session, for relative timestamps
+    pub start: DateTime<Local>,
+}
+
+impl Default for LineOptions {
+    fn default() -> Self {
+        Self {
+            format: LineFormat::default(),
+            timestamp: TimestampMode::default(),
+            port_tag: true,
+            start: Local::now(),
+        }
+    }
+}
+
+impl LineOptions {
+    /// Formats the `{time}` field, or `None` if timestamps are off.
+    ///
+    /// `previous` is the timestamp the delta modes count from; the first
+    /// line shows a delta of zero.
+    fn time_text(
+        &self,
+        timestamp: DateTime<Local>,
+        previous: Option<DateTime<Local>>,
+    ) -> Option<String> {
+        let since = |from: DateTime<Local>| format_offset(timestamp - from);
+        Some(match self.timestamp {
+            TimestampMode::Time => timestamp.format("%H:%M:%S%.3f").to_string(),
+            TimestampMode::Micros => timestamp.format("%H:%M:%S%.6f").to_string(),
+            TimestampMode::Date => timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            TimestampMode::Relative => since(self.start),
+            TimestampMode::Delta | TimestampMode::DeltaPort => since(previous.unwrap_or(timestamp)),
+            TimestampMode::Off => return None,
+        })
+    }
+}
+
+/// Formats a duration as `+S.mmm` seconds; negative ones (clock changes)
+/// show as zero.
+fn format_offset(delta: TimeDelta) -> String {
+    let ms = delta.num_milliseconds().max(0);
+    format!("+{}.{:03}", ms / 1000, ms % 1000)
+}
+
+/// What an entry holds.
+#[derive(Clone)]
+pub enum EntryKind {
+    /// Bytes received from the port
+    Data(Vec<u8>),
+    /// Bytes written to the port (local echo), rendered dim after `>>`
+    Sent(Vec<u8>),
+    /// Status text such as a connection change, rendered the same in every mode
+    Marker { text: String, color: Color },
+}
+
+/// One received line (or marker) in the display scrollback.
+#[derive(Clone)]
+pub struct Entry {
+    pub timestamp: DateTime<Local>,
+    pub port: Arc<str>,
+    /// Color of the port name, from the port config
+    pub port_color: Color,
+    pub kind: EntryKind,
+}
+
+impl Entry {
+    /// Bytes per hex dump row
+    const HEX_ROW: usize = 16;
+
+    /// Returns a copy with `data` appended, or `None` if the entry does
+    /// not hold received data.
+    pub fn appended(&self, data: &[u8]) -> Option<Entry> {
+        let EntryKind::Data(bytes) = &self.kind else {
+            return None;
+        };
+        Some(Entry {
+            timestamp: self.timestamp,
+            port: self.port.clone(),
+            port_color: self.port_color,
+            kind: EntryKind::Data([bytes, data].concat()),
+        })
+    }
+
+    /// Renders the entry as one or more display rows laid out by `options`.
+    ///
+    /// Only hex mode produces more than one row; continuation rows are
+    /// indented to line up under the first. `previous` is the timestamp
+    /// the delta timestamp modes count from.
+    pub fn render(
+        &self,
+        mode: ViewMode,
+        options: &LineOptions,
+        previous: Option<DateTime<Local>>,
+    ) -> Vec<Line<'static>> {
+        let (mut prefix, suffix) = self.fields(options, previous);
+        let with_suffix = |mut rows: Vec<Line<'static>>| {
+            if let Some(first) = rows.first_mut() {
+                first.spans.extend(suffix.iter().cloned());
+            }
+            rows
+        };
+
+        let data = match &self.kind {
+            EntryKind::Marker { text, color } => {
+                prefix.push(Span::styled(
+                    format!("--- {text} ---"),
+                    Style::default().fg(*color).add_modifier(Modifier::ITALIC),
+                ));
+                return with_suffix(vec![Line::from(prefix)]);
+            }
+            EntryKind::Data(data) => data,
+            EntryKind::Sent(data) => {
+                prefix.push(Span::raw(Direction::Tx.marker()));
+                let dim = Style::default().add_modifier(Modifier::DIM);
+                return with_suffix(Self::render_data(prefix, data, mode))
+                    .into_iter()
+                    .map(|row| row.patch_style(dim))
+                    .collect();
+            }
+        };
+        with_suffix(Self::render_data(prefix, data, mode))
+    }
+
+    /// Fills in the line format, returning the spans before the `{text}`
+    /// field and those after it.
+    fn fields(
+        &self,
+        options: &LineOptions,
+        previous: Option<DateTime<Local>>,
+    ) -> (Vec<Span<'static>>, Vec<Span<'static>>) {
+        let time = options.time_text(self.timestamp, previous);
+        let port_style = Style::default().fg(self.port_color);
+
+        let (mut prefix, mut suffix) = (Vec::new(), Vec::new());
+        let mut after_text = false;
+        for item in options.format.items() {
+            let (value, style) = match item.field {
+                None => (String::new(), Style::default()),
+                Some(Field::Time) => match &time {
+                    Some(time) => (time.clone(), Style::default()),
+                    None => continue,
+                },
+                Some(Field::Port) if options.port_tag => (self.port.to_string(), port_style),
+                Some(Field::Port) => continue,
+                Some(Field::Text) => {
+                    if !item.lead.is_empty() {
+                        prefix.push(Span::raw(item.lead.clone()));
+                    }
+                    if !item.trail.is_empty() {
+                        suffix.push(Span::raw(item.trail.clone()));
+                    }
+                    after_text = true;
+                    continue;
+                }
+            };
+            let span = Span::styled(format!("{}{value}{}", item.lead, item.trail), style);
+            if after_text {
+                suffix.push(Span::raw(" "));
+                suffix.push(span);
+            } else {
+                prefix.push(span);
+                prefix.push(Span::raw(" "));
+            }
+        }
+        (prefix, suffix)
+    }
+
+    /// Renders data rows in `mode`, the first one after `prefix`.
+    fn render_data(
+        mut prefix: Vec<Span<'static>>,
+        data: &[u8],
+        mode: ViewMode,
+    ) -> Vec<Line<'static>> {
+        match mode {
+            ViewMode::Text => {
+                prefix.push(Span::raw(String::from_utf8_lossy(data).into_owned()));
+                vec![Line::from(prefix)]
+            }
+            ViewMode::Escaped => {
+                prefix.extend(escaped_spans(data));
+                vec![Line::from(prefix)]
+            }
+            ViewMode::Hex => {
+                let indent = " ".repeat(Line::from(prefix.clone()).width());
+                let mut rows: Vec<Line<'static>> = data
+                    .chunks(Self::HEX_ROW)
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        let lead = if i == 0 {
+                            prefix.clone()
+                        } else {
+                            vec![Span::raw(indent.clone())]
+                        };
+                        let mut row = Line::from(lead);
+                        row.push_span(Span::raw(hex_row(i * Self::HEX_ROW, chunk)));
+                        row
+                    })
+                    .collect();
+                if rows.is_empty() {
+                    rows.push(Line::from(prefix));
+                }
+                rows
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+impl Entry {
+    /// Data received on `port` now, with the default color.
+    pub fn data(port: &str, bytes: &[u8]) -> Self {
+        Self {
+            timestamp: Local::now(),
+            port: port.into(),
+            port_color: Color::Reset,
+            kind: EntryKind::Data(bytes.to_vec()),
+        }
+    }
+}
+
+/// Formats one `xxd` row: offset, 2-byte hex groups, ASCII column.
+fn hex_row(offset: usize, chunk: &[u8]) -> String {
+    let mut row = format!("{offset:08x}: ");
+    for i in 0..Entry::HEX_ROW {
+        match chunk.get(i) {
+            Some(b) => row.push_str(&format!("{b:02x}")),
+            None => row.push_str("  "),
+        }
+        if i % 2 == 1 {
+            row.push(' ');
+        }
+    }
+    row.push(' ');
+    row.extend(chunk.iter().map(|&b| match b {
+        b' '..=b'~' => b as char,
+        _ => '.',
+    }));
+    row
+}
+
+/// Splits data into printable runs and highlighted `\xNN` escapes.
+///
+/// A literal backslash is shown as `\\` so it cannot be mistaken for an escape.
+fn escaped_spans(data: &[u8]) -> Vec<Span<'static>> {
+    let escape_style = Style::default().fg(Color::Magenta);
+    let mut spans = Vec::new();
+    let mut text = String::new();
+
+    for &b in data {
+        let escape = match b {
+            b'\\' => "\\\\".to_string(),
+            b' '..=b'~' => {
+                text.push(b as char);
+                continue;
+            }
+            _ => format!("\\x{b:02x}"),
+        };
+        if !text.is_empty() {
+            spans.push(Span::raw(std::mem::take(&mut text)));
+        }
+        spans.push(Span::styled(escape, escape_style));
+    }
+    if !text.is_empty() {
+        spans.push(Span::raw(text));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_render_modes() {
+        let entry = Entry::data("dut", b"Hello, binary\x00\x7f world\r\n");
+        let options = LineOptions::default();
+
+        let escaped = entry.render(ViewMode::Escaped, &options, None);
+        assert!(text(&escaped[0]).ends_with("[dut] Hello, binary\\x00\\x7f world\\x0d\\x0a"));
+
+        let hex = entry.render(ViewMode::Hex, &options, None);
+        assert_eq!(hex.len(), 2);
+        assert!(text(&hex[0]).ends_with(
+            "[dut] 00000000: 4865 6c6c 6f2c 2062 696e 6172 7900 7f20  Hello, binary.. "
+        ));
+        let second = text(&hex[1]);
+        assert!(second.ends_with("00000010: 776f 726c 640d 0a                        world.."));
+        assert_eq!(second.find("00000010"), text(&hex[0]).find("00000000"));
+    }
+
+    #[test]
+    fn test_line_format() {
+        let start = Local::now();
+        let entry = Entry {
+            timestamp: start + TimeDelta::milliseconds(1500),
+            ..Entry::data("dut", b"ok")
+        };
+        let mut options = LineOptions {
+            format: "{time} {port}: {text} <".parse().unwrap(),
+            timestamp: TimestampMode::Relative,
+            port_tag: true,
+            start,
+        };
+        let render = |options: &LineOptions, previous| {
+            text(&entry.render(ViewMode::Text, options, previous)[0])
+        };
+        assert_eq!(render(&options, None), "+1.500 dut: ok <");
+
+        // Empty fields drop out with their space
+        options.timestamp = TimestampMode::Delta;
+        options.port_tag = false;
+        assert_eq!(
+            render(&options, Some(start + TimeDelta::seconds(1))),
+            "+0.500 ok <"
+        );
+        assert_eq!(render(&options, None), "+0.000 ok <");
+        options.timestamp = TimestampMode::Off;
+        assert_eq!(render(&options, None), "ok <");
+
+        options.timestamp = TimestampMode::Date;
+        assert!(render(&options, None).starts_with(&start.format("%Y-%m-%d ").to_string()));
+    }
+}
@@ -0,0 +1,111 @@
+//! Display filters that hide entries, typed after `&` like in `less`.
+//!
+//! - `&text` shows only entries matching the regex
+//! - `&!text` hides entries matching the regex
+//! - `&@com1,com2` shows only entries from those ports
+//!
+//! Filters stack: an entry is shown only if it passes every one. Regexes
+//! are smart-case like search, and match the received text without the
+//! timestamp and port prefix, whatever the view mode.
+
+use std::{borrow::Cow, fmt};
+
+use regex::Regex;
+
+use super::{
+    display::compile_query,
+    entry::{Entry, EntryKind},
+};
+
+/// One filter of the display's filter stack.
+pub enum Filter {
+    /// Show only entries whose text matches
+    Include(Regex),
+    /// Hide entries whose text matches
+    Exclude(Regex),
+    /// Show only entries from these ports
+    Ports(Vec<String>),
+}
+
+impl Filter {
+    /// Parses a filter as typed after `&`.
+    pub fn parse(query: &str) -> Result<Self, String> {
+        if let Some(ports) = query.strip_prefix('@') {
+            let ports: Vec<String> = ports
+                .split(',')
+                .map(str::trim)
+                .filter(|port| !port.is_empty())
+                .map(String::from)
+                .collect();
+            if ports.is_empty() {
+                return Err("no ports after @".into());
+            }
+            return Ok(Filter::Ports(ports));
+        }
+
+        let (pattern, exclude) = match query.strip_prefix('!') {
+            Some(pattern) => (pattern, true),
+            None => (query, false),
+        };
+        let regex = compile_query(pattern).map_err(|e| e.to_string())?;
+        Ok(if exclude {
+            Filter::Exclude(regex)
+        } else {
+            Filter::Include(regex)
+        })
+    }
+
+    /// Returns true if the entry passes this filter.
+    pub fn accepts(&self, entry: &Entry) -> bool {
+        match self {
+            Filter::Include(regex) => regex.is_match(&entry_text(entry)),
+            Filter::Exclude(regex) => !regex.is_match(&entry_text(entry)),
+            Filter::Ports(ports) => ports.iter().any(|port| **port == *entry.port),
+        }
+    }
+}
+
+/// Formats the filter as typed, for the display title.
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::Include(regex) => write!(f, "{regex}"),
+            Filter::Exclude(regex) => write!(f, "!{regex}"),
+            Filter::Ports(ports) => write!(f, "@{}", ports.join(",")),
+        }
+    }
+}
+
+/// Returns the text filters match against: the data, or the marker text.
+fn entry_text(entry: &Entry) -> Cow<'_, str> {
+    match &entry.kind {
+        EntryKind::Data(data) | EntryKind::Sent(data) => String::from_utf8_lossy(data),
+        EntryKind::Marker { text, .. } => Cow::Borrowed(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        let include = Filter::parse("^temp").unwrap();
+        let exclude = Filter::parse("!DEBUG").unwrap();
+        let ports = Filter::parse("@com1, com2").unwrap();
+
+        // Matched against the text alone, not the `[time] [port]` prefix
+        assert!(include.accepts(&Entry::data("com1", "Temp 21.5".as_bytes())));
+        assert!(!include.accepts(&Entry::data("temp", "humidity 40".as_bytes())));
+        // Uppercase makes it case-sensitive
+        assert!(exclude.accepts(&Entry::data("com1", "debug: tick".as_bytes())));
+        assert!(!exclude.accepts(&Entry::data("com1", "DEBUG: tick".as_bytes())));
+        assert!(ports.accepts(&Entry::data("com2", "x".as_bytes())));
+        assert!(!ports.accepts(&Entry::data("com3", "x".as_bytes())));
+
+        assert_eq!(ports.to_string(), "@com1,com2");
+        assert_eq!(exclude.to_string(), "!DEBUG");
+        assert!(Filter::parse("@").is_err());
+        assert!(Filter::parse("!(").is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! Input history with Up/Down browsing and reverse search.
+//!
+//! Entries are appended to a history file as they are sent, so they
+//! survive restarts. The file is trimmed to `MAX_ENTRIES` on load.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+/// Previously sent input lines, oldest first.
+#[derive(Default)]
+pub struct History {
+    entries: Vec<String>,
+    /// History file, `None` keeps history in memory only
+    path: Option<PathBuf>,
+    /// Entry shown while browsing with Up/Down, `None` when editing a new line
+    browse: Option<usize>,
+    /// Line being edited when browsing started, restored by Down past the newest entry
+    draft: String,
+}
+
+impl History {
+    /// Maximum entries kept in memory and in the file
+    const MAX_ENTRIES: usize = 1000;
+
+    /// Loads history from `path`, trimming the file if it grew too long.
+    ///
+    /// A missing or unreadable file starts an empty history.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let mut entries: Vec<String> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| content.lines().map(String::from).collect())
+            .unwrap_or_default();
+
+        if entries.len() > Self::MAX_ENTRIES {
+            entries.drain(..entries.len() - Self::MAX_ENTRIES);
+            if let Some(path) = &path {
+                let _ = fs::write(path, entries.join("\n") + "\n");
+            }
+        }
+
+        Self {
+            entries,
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// Records a sent line and stops browsing.
+    ///
+    /// Empty lines and repeats of the newest entry are skipped. Failing
+    /// to write the file only loses persistence, so errors are ignored.
+    pub fn push(&mut self, line: &str) {
+        self.browse = None;
+        if line.is_empty() || self.entries.last().is_some_and(|last| last == line) {
+            return;
+        }
+
+        self.entries.push(line.to_string());
+        if self.entries.len() > Self::MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                let _ = writeln!(file, "{line}");
+            }
+        }
+    }
+
+    /// Steps to the next older entry (`Up`).
+    ///
+    /// `current` is remembered as the draft when browsing starts.
+    pub fn prev(&mut self, current: &str) -> Option<&str> {
+        let idx = match self.browse {
+            None => {
+                self.draft = current.to_string();
+                self.entries.len().checked_sub(1)?
+            }
+            Some(idx) => idx.saturating_sub(1),
+        };
+        self.browse = Some(idx);
+        Some(&self.entries[idx])
+    }
+
+    /// Steps to the next newer entry (`Down`), ending at the draft.
+    pub fn next(&mut self) -> Option<&str> {
+        let idx = self.browse?;
+        if idx + 1 < self.entries.len() {
+            self.browse = Some(idx + 1);
+            Some(&self.entries[idx + 1])
+        } else {
+            self.browse = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// Finds the newest entry before `before` that contains `query`.
+    ///
+    /// Pass `entries.len()` (or `usize::MAX`) to search from the newest.
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        let end = before.min(self.entries.len());
+        self.entries[..end]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    /// Returns the entry at `idx`.
+    pub fn get(&self, idx: usize) -> &str {
+        &self.entries[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browse_and_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+
+        let mut history = History::load(Some(path.clone()));
+        for line in ["AT", "AT+CSQ", "AT+CSQ", "", "ATI"] {
+            history.push(line);
+        }
+
+        assert_eq!(history.prev("draft"), Some("ATI"));
+        assert_eq!(history.prev(""), Some("AT+CSQ"));
+        assert_eq!(history.prev(""), Some("AT"));
+        assert_eq!(history.prev(""), Some("AT"));
+        assert_eq!(history.next(), Some("AT+CSQ"));
+        assert_eq!(history.next(), Some("ATI"));
+        assert_eq!(history.next(), Some("draft"));
+        assert_eq!(history.next(), None);
+
+        assert_eq!(history.search("CSQ", usize::MAX), Some(1));
+        assert_eq!(history.search("AT", 1), Some(0));
+        assert_eq!(history.search("AT", 0), None);
+
+        let reloaded = History::load(Some(path));
+        assert_eq!(reloaded.entries, ["AT", "AT+CSQ", "ATI"]);
+    }
+}
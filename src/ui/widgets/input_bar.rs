@@ -2,7 +2,16 @@
 //!
 //! Shows a `[ports]` button on the left and text input on the right.
 //! Supports modifier keys (Ctrl+Space to open send group).
+//!
+//! Text input understands `\r`, `\n`, `\t`, `\0`, `\\` and `\xHH` escapes.
+//! In hex mode (`Ctrl+x`) the input is parsed as hex bytes like `7F 00 A5`.
+//!
+//! Editing is readline-like (see `LineEditor`). Sent lines are kept in a
+//! persistent `History`, browsed with Up/Down and searched with `Ctrl+r`.
 
+use std::path::PathBuf;
+
+use bytes::Bytes;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     Frame,
@@ -12,39 +21,107 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-use super::focused_block;
+use crate::types::escape;
+
+use super::{focused_block, history::History, line_editor::LineEditor};
 
 /// Actions the input bar can request.
 pub enum InputBarAction {
     /// Open the send group popup to select target ports
     OpenSendGroup,
-    /// Send the text to selected ports
-    Send(String),
+    /// A `/command` typed in text mode (`//` sends a literal `/` instead)
+    Command(String),
+    /// Send bytes to selected ports; `line_ending` appends each port's `tx_line_ending`
+    Send { data: Bytes, line_ending: bool },
+    /// The input could not be parsed; it is kept for correction
+    Error(String),
+}
+
+/// State of an active `Ctrl+r` reverse history search.
+struct ReverseSearch {
+    /// Text typed after the prompt
+    query: String,
+    /// History index of the entry currently shown
+    found: Option<usize>,
+    /// Input before the search started, restored on cancel
+    original: String,
 }
 
 /// Text input bar at the bottom of the screen.
 ///
 /// Left side shows a clickable `[ports]` label, right side is
-/// the text input area with a cursor indicator.
+/// the text input area with the terminal cursor.
 pub struct InputBar {
-    /// Current input buffer
-    input: String,
+    /// Current input line
+    input: LineEditor,
+    /// Previously sent lines
+    history: History,
+    /// Active reverse search, if any
+    search: Option<ReverseSearch>,
+    /// Parse input as hex bytes instead of text
+    hex: bool,
 }
 
 impl InputBar {
-    /// Creates a new empty input bar.
-    pub fn new() -> Self {
+    /// Creates a new empty input bar, loading history from `history_path`.
+    pub fn new(history_path: Option<PathBuf>) -> Self {
         Self {
-            input: String::new(),
+            input: LineEditor::default(),
+            history: History::load(history_path),
+            search: None,
+            hex: false,
         }
     }
 
+    /// Returns true while a reverse history search is active.
+    ///
+    /// The search uses `Esc` to cancel, so the app must not quit on it.
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Turns the current input into a send action, clearing it on success.
+    ///
+    /// Hex input is sent as-is; text input gets the line ending unless
+    /// `line_ending` is false (`Alt+Enter`). A leading `//` sends text
+    /// that starts with `/`.
+    fn submit(&mut self, line_ending: bool) -> Option<InputBarAction> {
+        let text = self.input.text();
+        if !self.hex && text.starts_with('/') && !text.starts_with("//") {
+            self.history.push(text);
+            return Some(InputBarAction::Command(self.input.take()));
+        }
+
+        let parsed = if self.hex {
+            escape::parse_hex(text)
+        } else {
+            escape::unescape(text.strip_prefix('/').unwrap_or(text))
+        };
+        let data = match parsed {
+            Ok(data) => data,
+            Err(e) => return Some(InputBarAction::Error(e.to_string())),
+        };
+        // Empty hex input would send nothing at all
+        if self.hex && data.is_empty() {
+            return None;
+        }
+
+        self.history.push(text);
+        self.input.take();
+        Some(InputBarAction::Send {
+            data: Bytes::from(data),
+            line_ending: line_ending && !self.hex,
+        })
+    }
+
     /// Renders the input bar.
     ///
-    /// Layout: `[ports] <input text>_`
-    /// The underscore acts as a cursor indicator.
+    /// Layout: `[ports] <input text>`, or the reverse search prompt while
+    /// searching. The input scrolls horizontally to keep the cursor visible;
+    /// the terminal cursor is placed only when focused.
     pub fn render(&self, frame: &mut Frame, area: Rect, focused: bool) {
-        let block = focused_block(" Input ", focused);
+        let title = if self.hex { " Input [HEX] " } else { " Input " };
+        let block = focused_block(title, focused);
 
         // Get inner area (inside borders)
         let inner = block.inner(area);
@@ -69,37 +146,187 @@ impl InputBar {
 
         frame.render_widget(Paragraph::new(tab), chunks[0]);
 
-        // Input text with cursor
-        let input_text = format!("{}_", self.input);
-        frame.render_widget(Paragraph::new(input_text), chunks[1]);
+        let (text, before_cursor) = match &self.search {
+            Some(search) => {
+                let found = search.found.map_or("", |idx| self.history.get(idx));
+                let prompt = format!("(reverse-i-search)`{}", search.query);
+                (format!("{prompt}': {found}"), prompt)
+            }
+            None => (
+                self.input.text().to_string(),
+                self.input.before_cursor().to_string(),
+            ),
+        };
+
+        // Scroll so the cursor stays inside the input area
+        let input_area = chunks[1];
+        let cursor = Line::raw(before_cursor).width() as u16;
+        let scroll = cursor.saturating_sub(input_area.width.saturating_sub(1));
+        frame.render_widget(Paragraph::new(text).scroll((0, scroll)), input_area);
+
+        if focused {
+            frame.set_cursor_position((input_area.x + cursor - scroll, input_area.y));
+        }
+    }
+
+    /// Handles a key while reverse search is active.
+    ///
+    /// - Characters / `Backspace` -> Edit the query
+    /// - `Ctrl+r` -> Next older match
+    /// - `Enter` -> Send the match
+    /// - `Esc` / `Ctrl+g` -> Cancel, restoring the previous input
+    /// - Anything else -> Accept the match into the input for editing
+    fn handle_search_key(&mut self, key: KeyEvent) -> Option<InputBarAction> {
+        let search = self.search.as_mut()?;
+        match (key.modifiers, key.code) {
+            (KeyModifiers::CONTROL, KeyCode::Char('r')) => {
+                let before = search.found.unwrap_or(usize::MAX);
+                if let Some(idx) = self.history.search(&search.query, before) {
+                    search.found = Some(idx);
+                }
+                return None;
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('g')) | (_, KeyCode::Esc) => {
+                let search = self.search.take()?;
+                self.input.set(search.original);
+                return None;
+            }
+            (modifiers, KeyCode::Char(c)) if is_text(modifiers) => {
+                search.query.push(c);
+                search.found = self.history.search(&search.query, usize::MAX);
+                return None;
+            }
+            (_, KeyCode::Backspace) => {
+                search.query.pop();
+                search.found = self.history.search(&search.query, usize::MAX);
+                return None;
+            }
+            _ => {}
+        }
+
+        // Leave search with the match as the input
+        let search = self.search.take()?;
+        match search.found {
+            Some(idx) => self.input.set(self.history.get(idx)),
+            None => self.input.set(search.original),
+        }
+        match key.code {
+            KeyCode::Enter => self.submit(true),
+            _ => None,
+        }
     }
 
     /// Handles key input when this widget is focused.
     ///
     /// - `Ctrl+Space` -> Open send group popup
-    /// - Characters -> Append to input
-    /// - `Backspace` -> Delete last character
-    /// - `Enter` -> Send input text (if not empty)
+    /// - `Ctrl+x` -> Toggle hex input mode
+    /// - Characters (also AltGr ones) -> Insert at cursor
+    /// - `Left/Right`, `Home/End`, `Ctrl+a/e` -> Move cursor
+    /// - `Ctrl+Left/Right`, `Alt+b/f` -> Move by word
+    /// - `Backspace` / `Delete` -> Delete before / under cursor
+    /// - `Ctrl+w` / `Alt+d` -> Delete word before / after cursor
+    /// - `Ctrl+u` / `Ctrl+k` -> Delete to start / end of line
+    /// - `Up/Down` -> Browse history
+    /// - `Ctrl+r` -> Reverse search history
+    /// - `Enter` -> Send input (empty text sends just the line ending)
+    /// - `Alt+Enter` -> Send input without the line ending
     pub fn handle_key(&mut self, key: KeyEvent) -> Option<InputBarAction> {
+        if self.search.is_some() {
+            return self.handle_search_key(key);
+        }
+
         match (key.modifiers, key.code) {
             // Ctrl+Space opens the send group selector
-            (KeyModifiers::CONTROL, KeyCode::Char(' ')) => Some(InputBarAction::OpenSendGroup),
+            (KeyModifiers::CONTROL, KeyCode::Char(' ')) => {
+                return Some(InputBarAction::OpenSendGroup);
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('x')) => self.hex = !self.hex,
+            (KeyModifiers::CONTROL, KeyCode::Char('r')) => {
+                self.search = Some(ReverseSearch {
+                    query: String::new(),
+                    found: None,
+                    original: self.input.text().to_string(),
+                });
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('a')) | (_, KeyCode::Home) => self.input.home(),
+            (KeyModifiers::CONTROL, KeyCode::Char('e')) | (_, KeyCode::End) => self.input.end(),
+            (KeyModifiers::CONTROL, KeyCode::Char('w')) => self.input.delete_word_back(),
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => self.input.delete_to_start(),
+            (KeyModifiers::CONTROL, KeyCode::Char('k')) => self.input.delete_to_end(),
+            (KeyModifiers::ALT, KeyCode::Char('b')) | (KeyModifiers::CONTROL, KeyCode::Left) => {
+                self.input.word_left();
+            }
+            (KeyModifiers::ALT, KeyCode::Char('f')) | (KeyModifiers::CONTROL, KeyCode::Right) => {
+                self.input.word_right();
+            }
+            (KeyModifiers::ALT, KeyCode::Char('d')) => self.input.delete_word_forward(),
             // Regular character input
-            (_, KeyCode::Char(c)) => {
-                self.input.push(c);
-                None
+            (modifiers, KeyCode::Char(c)) if is_text(modifiers) => self.input.insert(c),
+            (_, KeyCode::Left) => self.input.left(),
+            (_, KeyCode::Right) => self.input.right(),
+            (_, KeyCode::Backspace) => self.input.delete_back(),
+            (_, KeyCode::Delete) => self.input.delete_forward(),
+            (_, KeyCode::Up) => {
+                if let Some(line) = self.history.prev(self.input.text()) {
+                    let line = line.to_string();
+                    self.input.set(line);
+                }
             }
-            // Backspace removes last character
-            (_, KeyCode::Backspace) => {
-                self.input.pop();
-                None
+            (_, KeyCode::Down) => {
+                if let Some(line) = self.history.next() {
+                    let line = line.to_string();
+                    self.input.set(line);
+                }
             }
+            (KeyModifiers::ALT, KeyCode::Enter) => return self.submit(false),
             // Enter sends the message (empty sends just line ending)
-            (_, KeyCode::Enter) => {
-                let text = std::mem::take(&mut self.input);
-                Some(InputBarAction::Send(text))
-            }
-            _ => None,
+            (_, KeyCode::Enter) => return self.submit(true),
+            _ => {}
         }
+        None
+    }
+}
+
+/// Returns true if a character typed with `modifiers` is text: plain,
+/// shifted, or AltGr (which Windows reports as Ctrl+Alt).
+fn is_text(modifiers: KeyModifiers) -> bool {
+    let modifiers = modifiers - KeyModifiers::SHIFT;
+    modifiers.is_empty() || modifiers == KeyModifiers::CONTROL | KeyModifiers::ALT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_altgr_input() {
+        let mut bar = InputBar::new(None);
+        let altgr = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        for (modifiers, c) in [
+            (KeyModifiers::SHIFT, 'A'),
+            (altgr, '@'),
+            (altgr | KeyModifiers::SHIFT, '|'),
+            (KeyModifiers::CONTROL, 'q'),
+        ] {
+            bar.handle_key(KeyEvent::new(KeyCode::Char(c), modifiers));
+        }
+        assert_eq!(bar.input.text(), "A@|");
+    }
+
+    #[test]
+    fn test_submit_slash() {
+        let mut bar = InputBar::new(None);
+        let mut submit = |text: &str| {
+            for c in text.chars() {
+                bar.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            }
+            bar.submit(true)
+        };
+
+        assert!(matches!(submit("/stat"), Some(InputBarAction::Command(c)) if c == "/stat"));
+        assert!(matches!(
+            submit("//x\\x41"),
+            Some(InputBarAction::Send { data, .. }) if data == "/xA"
+        ));
     }
 }
@@ -0,0 +1,183 @@
+//! Single-line text editing with a cursor, used by the input bar.
+//!
+//! Implements the usual readline-style motions and deletions. Words are
+//! runs of non-whitespace characters.
+
+/// Editable line of text with a cursor.
+#[derive(Default)]
+pub struct LineEditor {
+    text: String,
+    /// Byte offset of the cursor, always on a char boundary
+    cursor: usize,
+}
+
+impl LineEditor {
+    /// Returns the current text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the text before the cursor (for placing the terminal cursor).
+    pub fn before_cursor(&self) -> &str {
+        &self.text[..self.cursor]
+    }
+
+    /// Replaces the text and moves the cursor to the end.
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    /// Clears the text and returns what was there.
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.text)
+    }
+
+    /// Inserts a character at the cursor.
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Byte offset of the char before `pos`.
+    fn prev_boundary(&self, pos: usize) -> usize {
+        self.text[..pos]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Byte offset of the char after `pos`.
+    fn next_boundary(&self, pos: usize) -> usize {
+        self.text[pos..]
+            .chars()
+            .next()
+            .map_or(pos, |c| pos + c.len_utf8())
+    }
+
+    /// Start of the word before the cursor (skipping whitespace first).
+    fn word_start(&self) -> usize {
+        let before = self.text[..self.cursor].trim_end();
+        before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8())
+    }
+
+    /// End of the word after the cursor (skipping whitespace first).
+    fn word_end(&self) -> usize {
+        let after = &self.text[self.cursor..];
+        let skipped = after.len() - after.trim_start().len();
+        after[skipped..]
+            .char_indices()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(self.text.len(), |(i, _)| self.cursor + skipped + i)
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.prev_boundary(self.cursor);
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = self.next_boundary(self.cursor);
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    pub fn word_left(&mut self) {
+        self.cursor = self.word_start();
+    }
+
+    pub fn word_right(&mut self) {
+        self.cursor = self.word_end();
+    }
+
+    /// Deletes the char before the cursor (`Backspace`).
+    pub fn delete_back(&mut self) {
+        let start = self.prev_boundary(self.cursor);
+        self.text.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Deletes the char under the cursor (`Delete`).
+    pub fn delete_forward(&mut self) {
+        let end = self.next_boundary(self.cursor);
+        self.text.drain(self.cursor..end);
+    }
+
+    /// Deletes the word before the cursor (`Ctrl+w`).
+    pub fn delete_word_back(&mut self) {
+        let start = self.word_start();
+        self.text.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Deletes the word after the cursor (`Alt+d`).
+    pub fn delete_word_forward(&mut self) {
+        let end = self.word_end();
+        self.text.drain(self.cursor..end);
+    }
+
+    /// Deletes from the start of the line to the cursor (`Ctrl+u`).
+    pub fn delete_to_start(&mut self) {
+        self.text.drain(..self.cursor);
+        self.cursor = 0;
+    }
+
+    /// Deletes from the cursor to the end of the line (`Ctrl+k`).
+    pub fn delete_to_end(&mut self) {
+        self.text.truncate(self.cursor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing() {
+        let mut editor = LineEditor::default();
+        for c in "AT+CSQ".chars() {
+            editor.insert(c);
+        }
+        editor.home();
+        editor.right();
+        editor.right();
+        editor.delete_forward();
+        assert_eq!(editor.text(), "ATCSQ");
+
+        editor.set("at+cfun=1  µ reset");
+        editor.word_left();
+        assert_eq!(editor.before_cursor(), "at+cfun=1  µ ");
+        editor.word_left();
+        editor.delete_back();
+        assert_eq!(editor.text(), "at+cfun=1 µ reset");
+
+        editor.home();
+        editor.word_right();
+        editor.delete_word_forward();
+        assert_eq!(editor.text(), "at+cfun=1 reset");
+
+        editor.delete_word_back();
+        assert_eq!(editor.text(), " reset");
+        editor.end();
+        editor.delete_word_back();
+        assert_eq!(editor.text(), " ");
+
+        editor.set("abc");
+        editor.left();
+        editor.delete_to_end();
+        assert_eq!(editor.text(), "ab");
+        editor.left();
+        editor.delete_to_start();
+        assert_eq!(editor.text(), "b");
+    }
+}
@@ -8,11 +8,18 @@
 
 mod config_bar;
 mod display;
+mod entry;
+mod filter;
+mod history;
 mod input_bar;
+mod line_editor;
+mod panes;
 
 pub use config_bar::{ConfigAction, ConfigBar};
-pub use display::{Display, DisplayAction};
+pub use display::DisplayAction;
+pub use entry::{Entry, EntryKind};
 pub use input_bar::{InputBar, InputBarAction};
+pub use panes::Panes;
 
 use ratatui::{
     style::{Color, Style},
@@ -0,0 +1,479 @@
+//! Display panes: the combined view plus one per port or port group.
+//!
+//! Each pane is a `Display` with its own scrollback, search, filters and
+//! follow mode. Entries are shared (`Arc`) by every pane showing their
+//! port; lines that are not from a port (script output) only go to the
+//! combined pane. Panes are split by the layout's `Arrangement`, or one is zoomed
+//! to fill the area. Every pane starts with the layout's line format and
+//! timestamp mode; the port tag may be left out of single-port panes.
+
+use std::{collections::HashMap, sync::Arc};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Color,
+};
+
+use chrono::Local;
+
+use crate::{
+    config::layout::{self, Arrangement, PortTag},
+    serial::{self, PortEvent},
+};
+
+use super::{
+    display::{Display, DisplayAction},
+    entry::{Entry, EntryKind, LineOptions},
+};
+
+/// One display pane.
+struct Pane {
+    /// Ports shown; `None` for the combined pane
+    ports: Option<Vec<Arc<str>>>,
+    display: Display,
+    /// Display line id of each port's unfinished (partial) line
+    partial_lines: HashMap<Arc<str>, usize>,
+    /// Inner height at the last render, for key handling
+    height: usize,
+}
+
+impl Pane {
+    fn new(name: &str, ports: Option<Vec<Arc<str>>>, options: &PaneOptions) -> Self {
+        let single = ports.as_ref().is_some_and(|ports| ports.len() == 1);
+        let port_tag = match options.port_tag {
+            PortTag::Always => true,
+            PortTag::Auto => !single,
+            PortTag::Never => false,
+        };
+        let line = LineOptions {
+            port_tag,
+            ..options.line.clone()
+        };
+        Self {
+            ports,
+            display: Display::named(name).with_options(line),
+            partial_lines: HashMap::new(),
+            height: 0,
+        }
+    }
+
+    /// Returns true if the pane shows data from `port`.
+    fn shows(&self, port: &str) -> bool {
+        self.ports
+            .as_ref()
+            .is_none_or(|ports| ports.iter().any(|p| **p == *port))
+    }
+
+    /// Adds received data, appending to the port's partial line if any.
+    ///
+    /// Sent data gets a line of its own and ends the partial line.
+    /// Entries are taken from `shared` so other panes can reuse them.
+    fn push_data(&mut self, event: &PortEvent, port_color: Color, shared: &mut SharedEntries) {
+        let PortEvent {
+            port,
+            data,
+            timestamp,
+            partial,
+            direction,
+        } = event;
+        let new_entry = |kind| Entry {
+            timestamp: *timestamp,
+            port: port.clone(),
+            port_color,
+            kind,
+        };
+
+        if *direction == serial::Direction::Tx {
+            self.partial_lines.remove(port);
+            let entry = shared.get(None, || new_entry(EntryKind::Sent(data.to_vec())));
+            self.display.push_entry(entry);
+            return;
+        }
+
+        let continued = self.partial_lines.remove(port).and_then(|id| {
+            let base = self.display.entry(id)?.clone();
+            let entry = shared.get(Some(&base), || {
+                base.appended(data)
+                    .unwrap_or_else(|| new_entry(EntryKind::Data(data.to_vec())))
+            });
+            self.display.replace_entry(id, entry).then_some(id)
+        });
+
+        let id = continued.unwrap_or_else(|| {
+            let entry = shared.get(None, || new_entry(EntryKind::Data(data.to_vec())));
+            self.display.push_entry(entry)
+        });
+
+        if *partial {
+            self.partial_lines.insert(port.clone(), id);
+        }
+    }
+}
+
+/// Entries made from one event, so the panes showing its port share them
+/// instead of each keeping a copy.
+#[derive(Default)]
+struct SharedEntries(Vec<(Option<Arc<Entry>>, Arc<Entry>)>);
+
+impl SharedEntries {
+    /// Returns the entry made by extending `base` (a new line if `None`),
+    /// calling `make` only if no pane has made it yet.
+    fn get(&mut self, base: Option<&Arc<Entry>>, make: impl FnOnce() -> Entry) -> Arc<Entry> {
+        let made = self.0.iter().find(|(other, _)| match (other, base) {
+            (Some(other), Some(base)) => Arc::ptr_eq(other, base),
+            (other, base) => other.is_none() && base.is_none(),
+        });
+        if let Some((_, entry)) = made {
+            return entry.clone();
+        }
+        let entry = Arc::new(make());
+        self.0.push((base.cloned(), entry.clone()));
+        entry
+    }
+}
+
+/// Line options from the layout that new panes start with.
+struct PaneOptions {
+    line: LineOptions,
+    port_tag: PortTag,
+}
+
+/// The display panes and how they are laid out.
+pub struct Panes {
+    /// The combined pane first, then the port panes
+    panes: Vec<Pane>,
+    options: PaneOptions,
+    /// No groups configured: ports get a pane on their first data
+    per_port: bool,
+    arrangement: Arrangement,
+    /// Index of the pane receiving keys
+    focused: usize,
+    /// Only the focused pane is shown
+    zoomed: bool,
+}
+
+impl Panes {
+    /// Creates the panes for `layout`.
+    ///
+    /// Without groups, each of `ports` gets a pane now and ports seen later
+    /// get one when they first send data.
+    pub fn new(layout: layout::Layout, ports: &[Arc<str>]) -> Self {
+        let options = PaneOptions {
+            line: LineOptions {
+                format: layout.line_format,
+                timestamp: layout.timestamp,
+                port_tag: true,
+                start: Local::now(),
+            },
+            port_tag: layout.port_tag,
+        };
+        let mut panes = vec![Pane::new("Display", None, &options)];
+        let per_port = layout.panes.is_empty();
+        if per_port {
+            panes.extend(
+                ports
+                    .iter()
+                    .map(|port| Pane::new(port, Some(vec![port.clone()]), &options)),
+            );
+        } else {
+            panes.extend(layout.panes.into_iter().map(|group| {
+                let ports = group.ports.into_iter().map(Arc::from).collect();
+                Pane::new(&group.name, Some(ports), &options)
+            }));
+        }
+
+        Self {
+            panes,
+            options,
+            per_port,
+            arrangement: layout.arrangement,
+            focused: 0,
+            zoomed: !layout.split,
+        }
+    }
+
+    /// Adds received data to every pane showing its port.
+    pub fn push_data(&mut self, event: &PortEvent, port_color: Color) {
+        if self.per_port && !self.panes[1..].iter().any(|pane| pane.shows(&event.port)) {
+            let port = event.port.clone();
+            let pane = Pane::new(&port, Some(vec![port.clone()]), &self.options);
+            self.panes.push(pane);
+        }
+        let mut shared = SharedEntries::default();
+        for pane in self.panes.iter_mut().filter(|pane| pane.shows(&event.port)) {
+            pane.push_data(event, port_color, &mut shared);
+        }
+    }
+
+    /// Adds a marker line to every pane showing its port, or only to the
+    /// combined pane if it is not from a port.
+    ///
+    /// Data after a marker starts on a fresh line.
+    pub fn push_marker(&mut self, entry: Entry) {
+        let entry = Arc::new(entry);
+        for pane in self.panes.iter_mut().filter(|pane| pane.shows(&entry.port)) {
+            pane.partial_lines.remove(&entry.port);
+            pane.display.push_entry(entry.clone());
+        }
+    }
+
+    /// Drops the pane of a removed port, and its partial line everywhere.
+    ///
+    /// Group panes keep the name, so the port shows there again if it
+    /// comes back.
+    pub fn remove_port(&mut self, port: &str) {
+        for pane in &mut self.panes {
+            pane.partial_lines.remove(port);
+        }
+        if !self.per_port {
+            return;
+        }
+        if let Some(idx) = self.panes[1..].iter().position(|pane| {
+            pane.ports
+                .as_ref()
+                .is_some_and(|ports| ports.len() == 1 && *ports[0] == *port)
+        }) {
+            self.panes.remove(idx + 1);
+            if self.focused > idx {
+                self.focused -= 1;
+            }
+        }
+    }
+
+    /// Moves panes showing `old` over to `new`, renaming its own pane.
+    ///
+    /// Lines already shown keep the old name.
+    pub fn rename_port(&mut self, old: &str, new: Arc<str>) {
+        for pane in &mut self.panes {
+            pane.partial_lines.remove(old);
+            let Some(ports) = &mut pane.ports else {
+                continue;
+            };
+            for port in ports.iter_mut() {
+                if **port == *old {
+                    *port = new.clone();
+                }
+            }
+            if self.per_port && ports.len() == 1 && ports[0] == new {
+                pane.display.rename(&*new);
+            }
+        }
+    }
+
+    /// Returns the displays of the panes showing `port`.
+    pub fn displays_for<'a>(&'a mut self, port: &'a str) -> impl Iterator<Item = &'a mut Display> {
+        self.panes
+            .iter_mut()
+            .filter(move |pane| pane.shows(port))
+            .map(|pane| &mut pane.display)
+    }
+
+    /// Clears every pane.
+    pub fn clear(&mut self) {
+        for pane in &mut self.panes {
+            pane.display.clear();
+            pane.partial_lines.clear();
+        }
+    }
+
+    /// Returns true if the focused pane is in a mode that `Esc` leaves.
+    pub fn is_modal(&self) -> bool {
+        self.panes[self.focused].display.is_modal()
+    }
+
+    /// Renders the zoomed pane, or all panes split by the arrangement.
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool) {
+        let shown: Vec<usize> = if self.zoomed {
+            vec![self.focused]
+        } else {
+            (0..self.panes.len()).collect()
+        };
+        let areas = split(self.arrangement, shown.len(), area);
+
+        for (idx, area) in shown.into_iter().zip(areas) {
+            let pane = &mut self.panes[idx];
+            pane.height = area.height.saturating_sub(2) as usize; // minus borders
+            pane.display
+                .render(frame, area, focused && idx == self.focused);
+        }
+    }
+
+    /// Handles key input when the display area is focused.
+    ///
+    /// - `]` / `[` -> Focus the next / previous pane
+    /// - `z` -> Toggle zoom on the focused pane
+    /// - `L` -> Cycle the split arrangement (horizontal / vertical / grid)
+    ///
+    /// Other keys, and all keys while the focused pane is in a prompt or
+    /// visual mode, go to the focused pane.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<DisplayAction> {
+        if !self.is_modal() {
+            match (key.modifiers, key.code) {
+                (_, KeyCode::Char(']')) => {
+                    self.focused = (self.focused + 1) % self.panes.len();
+                    return None;
+                }
+                (_, KeyCode::Char('[')) => {
+                    self.focused = (self.focused + self.panes.len() - 1) % self.panes.len();
+                    return None;
+                }
+                (_, KeyCode::Char('z')) => {
+                    self.zoomed = !self.zoomed;
+                    return None;
+                }
+                (KeyModifiers::SHIFT, KeyCode::Char('L')) => {
+                    self.arrangement = self.arrangement.next();
+                    self.zoomed = false;
+                    return None;
+                }
+                _ => {}
+            }
+        }
+
+        let pane = &mut self.panes[self.focused];
+        pane.display.handle_key(key, pane.height)
+    }
+}
+
+/// Splits `area` into `count` pane areas.
+fn split(arrangement: Arrangement, count: usize, area: Rect) -> Vec<Rect> {
+    let even = |direction, count: usize, area| {
+        Layout::default()
+            .direction(direction)
+            .constraints(vec![Constraint::Ratio(1, count as u32); count])
+            .split(area)
+            .to_vec()
+    };
+
+    match arrangement {
+        _ if count <= 1 => vec![area],
+        Arrangement::Horizontal => even(Direction::Horizontal, count, area),
+        Arrangement::Vertical => even(Direction::Vertical, count, area),
+        Arrangement::Grid => {
+            let cols = count.isqrt() + usize::from(count.isqrt().pow(2) < count);
+            let rows = count.div_ceil(cols);
+            even(Direction::Vertical, rows, area)
+                .into_iter()
+                .enumerate()
+                .flat_map(|(row, area)| {
+                    let in_row = cols.min(count - row * cols);
+                    even(Direction::Horizontal, in_row, area)
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::layout::PaneGroup;
+
+    use super::*;
+
+    #[test]
+    fn test_routing() {
+        let layout = layout::Layout {
+            panes: vec![PaneGroup {
+                name: "boards".into(),
+                ports: vec!["com1".into(), "com2".into()],
+            }],
+            ..Default::default()
+        };
+        let mut panes = Panes::new(layout, &[]);
+        panes.push_data(&PortEvent::rx("com1", b"a"), Color::Reset);
+        panes.push_data(&PortEvent::rx("modem", b"b"), Color::Reset);
+        panes.push_marker(Entry {
+            timestamp: Local::now(),
+            port: "boot.rhai".into(),
+            port_color: Color::Reset,
+            kind: EntryKind::Marker {
+                text: "started".into(),
+                color: Color::Cyan,
+            },
+        });
+
+        // Groups are fixed; everything lands in the combined pane
+        assert_eq!(panes.panes.len(), 2);
+        let boards = panes.panes[1].display.get_selected_text();
+        assert!(boards.ends_with("[com1] a"));
+        let combined = panes.panes[0].display.get_selected_text();
+        assert!(combined.ends_with("[boot.rhai] --- started ---"));
+
+        // Without groups, new ports get their own pane
+        let mut panes = Panes::new(layout::Layout::default(), &["com1".into()]);
+        panes.push_data(&PortEvent::rx("com2", b"x"), Color::Reset);
+        panes.push_data(&PortEvent::rx("com1", b"y"), Color::Reset);
+        assert_eq!(panes.panes.len(), 3);
+        assert!(
+            panes.panes[2]
+                .display
+                .get_selected_text()
+                .ends_with("[com2] x")
+        );
+    }
+
+    #[test]
+    fn test_port_changes() {
+        let mut panes = Panes::new(layout::Layout::default(), &["com1".into(), "com2".into()]);
+        let partial = PortEvent {
+            partial: true,
+            ..PortEvent::rx("com1", b"a")
+        };
+        panes.push_data(&partial, Color::Reset);
+        panes.push_data(&PortEvent::rx("com1", b"b"), Color::Reset);
+
+        // Both panes hold the same grown entry
+        let entry = |pane: &Pane| pane.display.entry(0).unwrap().clone();
+        assert!(Arc::ptr_eq(
+            &entry(&panes.panes[0]),
+            &entry(&panes.panes[1])
+        ));
+        assert!(matches!(&entry(&panes.panes[1]).kind, EntryKind::Data(data) if data == b"ab"));
+
+        panes.focused = 2;
+        panes.rename_port("com1", "dut".into());
+        panes.remove_port("com2");
+        assert_eq!(panes.panes.len(), 2);
+        assert_eq!(panes.focused, 1);
+        panes.push_data(&PortEvent::rx("dut", b"c"), Color::Reset);
+        assert!(
+            panes.panes[1]
+                .display
+                .get_selected_text()
+                .ends_with("[dut] c")
+        );
+        assert_eq!(
+            panes.panes[1].display.entry(1).unwrap().port.as_ref(),
+            "dut"
+        );
+    }
+
+    #[test]
+    fn test_port_tag() {
+        let layout = layout::Layout {
+            port_tag: PortTag::Auto,
+            timestamp: layout::TimestampMode::Off,
+            ..Default::default()
+        };
+        let mut panes = Panes::new(layout, &["dut".into()]);
+        panes.push_data(&PortEvent::rx("dut", b"ok"), Color::Reset);
+        assert_eq!(panes.panes[0].display.get_selected_text(), "[dut] ok");
+        assert_eq!(panes.panes[1].display.get_selected_text(), "ok");
+    }
+
+    #[test]
+    fn test_split() {
+        let area = Rect::new(0, 0, 90, 40);
+        let grid = split(Arrangement::Grid, 5, area);
+        // Three on top, two below
+        assert_eq!(grid.len(), 5);
+        assert_eq!((grid[2].x, grid[2].width, grid[2].height), (60, 30, 20));
+        assert_eq!((grid[4].x, grid[4].y, grid[4].width), (45, 20, 45));
+
+        let columns = split(Arrangement::Horizontal, 3, area);
+        assert_eq!(columns[1], Rect::new(30, 0, 30, 40));
+        assert_eq!(split(Arrangement::Vertical, 1, area), [area]);
+    }
+}